@@ -3,12 +3,15 @@ use std::fs::create_dir_all;
 
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
-use cw20::{
-    AllAccountsResponse, AllAllowancesResponse, AllowanceResponse, BalanceResponse,
-    TokenInfoResponse,
-};
+use cw20::{AllAllowancesResponse, AllowanceResponse};
 
-//use cw20_base::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use club_staking::msg::{
+    BondsResponse, ClubAuctionResponse, ClubOwnershipDetailsResponse,
+    ClubOwnershipHistoryResponse, ClubPriceResponse, ClubRankingResponse, ClubRegistryResponse,
+    ClubResponse, ClubsResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, ListingsResponse,
+    MigrateMsg, OffersResponse, OwnedClubsResponse, PendingAdminResponse, QueryMsg, ReceiveMsg,
+    RewardAmountResponse, RolesResponse, StakerPortfolioResponse, StakesResponse,
+};
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
@@ -16,14 +19,30 @@ fn main() {
     create_dir_all(&out_dir).unwrap();
     remove_schemas(&out_dir).unwrap();
 
-/*
     export_schema(&schema_for!(InstantiateMsg), &out_dir);
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
+    export_schema(&schema_for!(ReceiveMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
-*/
+    export_schema(&schema_for!(MigrateMsg), &out_dir);
+
     export_schema(&schema_for!(AllowanceResponse), &out_dir);
-    export_schema(&schema_for!(BalanceResponse), &out_dir);
-    export_schema(&schema_for!(TokenInfoResponse), &out_dir);
     export_schema(&schema_for!(AllAllowancesResponse), &out_dir);
-    export_schema(&schema_for!(AllAccountsResponse), &out_dir);
+    export_schema(&schema_for!(StakesResponse), &out_dir);
+    export_schema(&schema_for!(BondsResponse), &out_dir);
+    export_schema(&schema_for!(ClubOwnershipDetailsResponse), &out_dir);
+    export_schema(&schema_for!(ClubsResponse), &out_dir);
+    export_schema(&schema_for!(ClubResponse), &out_dir);
+    export_schema(&schema_for!(ClubRegistryResponse), &out_dir);
+    export_schema(&schema_for!(ClubAuctionResponse), &out_dir);
+    export_schema(&schema_for!(ListingsResponse), &out_dir);
+    export_schema(&schema_for!(OffersResponse), &out_dir);
+    export_schema(&schema_for!(ClubPriceResponse), &out_dir);
+    export_schema(&schema_for!(ClubOwnershipHistoryResponse), &out_dir);
+    export_schema(&schema_for!(OwnedClubsResponse), &out_dir);
+    export_schema(&schema_for!(ClubRankingResponse), &out_dir);
+    export_schema(&schema_for!(RewardAmountResponse), &out_dir);
+    export_schema(&schema_for!(ConfigResponse), &out_dir);
+    export_schema(&schema_for!(RolesResponse), &out_dir);
+    export_schema(&schema_for!(StakerPortfolioResponse), &out_dir);
+    export_schema(&schema_for!(PendingAdminResponse), &out_dir);
 }
@@ -1,55 +1,55 @@
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult,
-    Storage, Uint128, WasmMsg,
+    attr, from_binary, to_binary, Addr, Api, Binary, BlockInfo, Decimal, Deps, DepsMut, Empty, Env, Event, Fraction, MessageInfo, Order, Response, StdError, StdResult,
+    Storage, Timestamp, Uint128, Uint256, WasmMsg,
 };
 
-use cw2::set_contract_version;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use cw2::{get_contract_version, set_contract_version};
+use cw_storage_plus::{Bound, Map, U64Key};
 use cw20::{
-    AllowanceResponse, BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20ReceiveMsg, Expiration,
+    AllowanceResponse, BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg, Cw20ReceiveMsg,
+    EmbeddedLogo, Expiration, Logo,
 };
 
 use crate::allowances::{
     deduct_allowance, execute_burn_from, execute_decrease_allowance, execute_increase_allowance,
     execute_send_from, execute_transfer_from, query_allowance,
 };
+use crate::enumerable::{
+    query_all_allowances, query_all_bonds, query_all_clubs, query_all_stakes, query_club_ranking,
+    query_club_listings, query_club_offers, query_club_ownership_history, query_club_registry,
+};
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::msg::{
+    BondsResponse, ClubAuctionResponse, ClubOwnershipDetailsResponse, ClubPriceResponse, ClubResponse, ConfigResponse, HighestBid, OwnedClubsResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, PendingAdminResponse,
+    PortfolioBond, PortfolioClub, PortfolioStake, QueryMsg, ReceiveMsg, RewardAmountResponse,
+    RoleHolder, RolesResponse, StakerPortfolioResponse, StakesResponse,
+};
 use crate::state::{
-    ClubOwnershipDetails, ClubPreviousOwnerDetails, ClubStakingDetails, ClubBondingDetails, Config, 
-    CLUB_OWNERSHIP_DETAILS, CLUB_PREVIOUS_OWNER_DETAILS, CLUB_STAKING_DETAILS, CLUB_BONDING_DETAILS,
-    CONFIG, CONTRACT_WALLET, REWARD,
+    club_staking_details, AuctionBid, ClubAuction, ClubInfo, ClubListing, ClubOffer, ClubOwnershipDetails, ClubPreviousOwnerDetails, ClubStakingDetails, ClubBondingDetails, Config, 
+    ClubStatus, PenaltyDestination, PendingAdmin, PricingModel, Role, CLUBS, CLUB_AUCTIONS, CLUB_LISTINGS, CLUB_OFFERS, CLUB_OWNERSHIP_DETAILS, CLUB_PREVIOUS_OWNER_DETAILS, CLUB_STAKING_DETAILS_V0_10,
+    ClubOwnershipRecord, CLUB_OWNERSHIP_HISTORY, CLUB_OWNERSHIP_HISTORY_LENGTH, CLUB_PREVIOUS_OWNER_DETAILS_V0_14,
+    BONDER_CLUBS, OWNER_CLUBS, PREVIOUS_OWNER_CLUBS,
+    leaderboard_club_name, leaderboard_key, CLUB_LEADERBOARD, CLUB_STAKING_TOTALS, CLUB_BONDING_DETAILS, CLUB_BOND_SEQUENCE, CLUB_REWARD_INDEX, CONFIG, GLOBAL_REWARD_INDEX,
+    TOTAL_STAKED, CONTRACT_WALLET, ESCROWED_FUNDS, LEGACY_CLUB_STAKING_DETAILS, LEGACY_CONFIG, PENALTY_POOL, PENDING_ADMIN, REWARD, ROLES,
 };
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:club-staking";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
-const MAIN_WALLET: &str = "terra1t3czdl5h4w4qwgkzs80fdstj0z7rfv9v2j6uh3";
-
-const CLUB_PRICE: u128 = 1000000000u128;
-
 const INCREASE_STAKE: bool = true;
 const DECREASE_STAKE: bool = false;
 const IMMEDIATE_WITHDRAWAL: bool = true;
 const NO_IMMEDIATE_WITHDRAWAL: bool = false;
 
-// Reward to club owner for buying
-const CLUB_BUYING_REWARD_AMOUNT: u128 = 100u128;
-
-// Reward to club staker for staking 
-const CLUB_STAKING_REWARD_AMOUNT: u128 = 0u128;
-
-// This is 21 day locking period in seconds, after buying a club 
-const CLUB_LOCKING_DURATION: u64 = 1814400u64;
-
-// This is locking period in seconds, after staking in club. 
-// No longer applicable so setting it to 0
-const CLUB_STAKING_DURATION: u64 = 0u64;
-
-// this is 7 day bonding period in seconds, after withdrawing a stake
-const CLUB_BONDING_DURATION: u64 = 604800u64;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -63,9 +63,571 @@ pub fn instantiate(
     let config = Config {
         cw20_token_address: deps.api.addr_validate(&msg.cw20_token_address)?,
         admin_address: deps.api.addr_validate(&msg.admin_address)?,
+        treasury_address: deps.api.addr_validate(&msg.treasury_address)?,
+        protocol_fee_percentage: msg.protocol_fee_percentage,
+        early_withdrawal_penalty_percentage: msg.early_withdrawal_penalty_percentage,
+        penalty_destination: msg.penalty_destination,
+        club_price: msg.club_price,
+        club_buying_reward_amount: msg.club_buying_reward_amount,
+        club_staking_reward_amount: msg.club_staking_reward_amount,
+        club_locking_duration: msg.club_locking_duration,
+        club_bonding_duration: msg.club_bonding_duration,
+        owner_reward_percentage: msg.owner_reward_percentage,
+        winner_club_reward_percentage: msg.winner_club_reward_percentage,
+        all_stakers_reward_percentage: msg.all_stakers_reward_percentage,
+        club_creation_fee: msg.club_creation_fee,
+        auction_extension_duration: msg.auction_extension_duration,
+        pricing_model: msg.pricing_model,
+        max_clubs_per_owner: msg.max_clubs_per_owner,
     };
+    validate_config(&config)?;
     CONFIG.save(deps.storage, &config)?;
-    Ok(Response::default())
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "instantiate"),
+        attr("admin", config.admin_address),
+    ]))
+}
+
+fn validate_config(config: &Config) -> Result<(), ContractError> {
+    let hundred = Uint128::from(100u128);
+    if config.protocol_fee_percentage > hundred {
+        return Err(ContractError::InvalidPercentage {
+            field: String::from("protocol_fee_percentage"),
+            value: config.protocol_fee_percentage,
+        });
+    }
+    if config.early_withdrawal_penalty_percentage > hundred {
+        return Err(ContractError::InvalidPercentage {
+            field: String::from("early_withdrawal_penalty_percentage"),
+            value: config.early_withdrawal_penalty_percentage,
+        });
+    }
+    let split_total = config.owner_reward_percentage
+        + config.winner_club_reward_percentage
+        + config.all_stakers_reward_percentage;
+    if split_total != hundred {
+        return Err(ContractError::InvalidRewardSplit { total: split_total });
+    }
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+    if stored.contract != CONTRACT_NAME {
+        return Err(ContractError::CannotMigrate {
+            previous_contract: stored.contract,
+        });
+    }
+    let stored_version = parse_version(&stored.version)?;
+    if stored_version > parse_version(CONTRACT_VERSION)? {
+        return Err(ContractError::CannotMigrateVersion {
+            previous_version: stored.version,
+            new_version: CONTRACT_VERSION.to_string(),
+        });
+    }
+
+    // State transformations, oldest first. Each one brings the state up to
+    // the layout of the version it is named after.
+    if stored_version < (0, 10, 0) {
+        migrate_config_to_v0_10(deps.storage, deps.api, msg)?;
+        migrate_staking_details_to_v0_10(deps.storage)?;
+    }
+    if stored_version < (0, 11, 0) {
+        migrate_staking_details_to_v0_11(deps.storage)?;
+    }
+    if stored_version < (0, 12, 0) {
+        migrate_total_staked_to_v0_12(deps.storage)?;
+    }
+    if stored_version < (0, 13, 0) {
+        migrate_leaderboard_to_v0_13(deps.storage)?;
+    }
+    if stored_version < (0, 14, 0) {
+        migrate_club_registry_to_v0_14(deps.storage, &env)?;
+    }
+    if stored_version < (0, 15, 0) {
+        migrate_ownership_history_to_v0_15(deps.storage)?;
+    }
+    if stored_version < (0, 16, 0) {
+        migrate_owner_clubs_to_v0_16(deps.storage)?;
+    }
+    let mut invalid_club_names = Vec::new();
+    if stored_version < (0, 17, 0) {
+        invalid_club_names = migrate_club_names_to_v0_17(deps.storage)?;
+        migrate_bond_ids_to_v0_17(deps.storage)?;
+        migrate_escrowed_funds_to_v0_17(deps.storage)?;
+        migrate_portfolio_indexes_to_v0_17(deps.storage)?;
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "migrate"),
+            attr("from_version", stored.version),
+            attr("to_version", CONTRACT_VERSION),
+        ])
+        .add_attributes(
+            invalid_club_names
+                .into_iter()
+                .map(|club_name| attr("invalid_club_name", club_name)),
+        ))
+}
+
+/// Splits a "major.minor.patch" version so that versions compare numerically
+fn parse_version(version: &str) -> StdResult<(u64, u64, u64)> {
+    let parts: Vec<u64> = version
+        .split('.')
+        .map(|part| part.parse::<u64>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| StdError::generic_err(format!("Invalid contract version {}", version)))?;
+    if parts.len() != 3 {
+        return Err(StdError::generic_err(format!("Invalid contract version {}", version)));
+    }
+    Ok((parts[0], parts[1], parts[2]))
+}
+
+/// 0.10.0 moved the club economics from constants into Config and added
+/// the treasury, protocol fee and early withdrawal penalty settings
+fn migrate_config_to_v0_10(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    msg: MigrateMsg,
+) -> Result<(), ContractError> {
+    let legacy = LEGACY_CONFIG.load(storage)?;
+    let treasury_address = match msg.treasury_address {
+        Some(address) => api.addr_validate(&address)?,
+        None => legacy.admin_address.clone(),
+    };
+    let config = Config {
+        cw20_token_address: legacy.cw20_token_address,
+        admin_address: legacy.admin_address,
+        treasury_address,
+        protocol_fee_percentage: msg.protocol_fee_percentage.unwrap_or_default(),
+        early_withdrawal_penalty_percentage: msg
+            .early_withdrawal_penalty_percentage
+            .unwrap_or_default(),
+        penalty_destination: msg.penalty_destination.unwrap_or(PenaltyDestination::Burn),
+        club_price: Uint128::from(1000000000u128),
+        club_buying_reward_amount: Uint128::from(100u128),
+        club_staking_reward_amount: Uint128::zero(),
+        club_locking_duration: 1814400u64,
+        club_bonding_duration: 604800u64,
+        owner_reward_percentage: Uint128::from(1u128),
+        winner_club_reward_percentage: Uint128::from(19u128),
+        all_stakers_reward_percentage: Uint128::from(80u128),
+        club_creation_fee: Uint128::zero(),
+        auction_extension_duration: 0u64,
+        pricing_model: PricingModel::Fixed,
+        max_clubs_per_owner: 0u32,
+    };
+    validate_config(&config)?;
+    CONFIG.save(storage, &config)?;
+    Ok(())
+}
+
+/// 0.10.0 dropped the unused staking_start_timestamp and staking_duration
+/// fields from ClubStakingDetails
+fn migrate_staking_details_to_v0_10(storage: &mut dyn Storage) -> StdResult<()> {
+    let all_clubs: Vec<String> = LEGACY_CLUB_STAKING_DETAILS
+        .keys(storage, None, None, Order::Ascending)
+        .map(String::from_utf8)
+        .collect::<Result<_, _>>()?;
+    for club_name in all_clubs {
+        let legacy_stakes = LEGACY_CLUB_STAKING_DETAILS.load(storage, club_name.clone())?;
+        let stakes: Vec<ClubStakingDetails> = legacy_stakes
+            .into_iter()
+            .map(|stake| ClubStakingDetails {
+                club_name: stake.club_name,
+                staker_address: stake.staker_address,
+                staked_amount: stake.staked_amount,
+                reward_amount: stake.reward_amount,
+                ..Default::default()
+            })
+            .collect();
+        CLUB_STAKING_DETAILS_V0_10.save(storage, club_name, &stakes)?;
+    }
+    Ok(())
+}
+
+/// 0.11.0 moved the stakes from one vector per club to one entry per
+/// (club, staker), and keeps a running total per club
+fn migrate_staking_details_to_v0_11(storage: &mut dyn Storage) -> StdResult<()> {
+    let all_clubs: Vec<String> = CLUB_STAKING_DETAILS_V0_10
+        .keys(storage, None, None, Order::Ascending)
+        .map(String::from_utf8)
+        .collect::<Result<_, _>>()?;
+    for club_name in all_clubs {
+        let stakes = CLUB_STAKING_DETAILS_V0_10.load(storage, club_name.clone())?;
+        let mut total_staked = Uint128::zero();
+        for stake in stakes {
+            total_staked += stake.staked_amount;
+            club_staking_details().save(
+                storage,
+                (club_name.clone(), stake.staker_address.clone()),
+                &stake,
+            )?;
+        }
+        if total_staked > Uint128::zero() {
+            CLUB_STAKING_TOTALS.save(storage, club_name.clone(), &total_staked)?;
+        }
+        CLUB_STAKING_DETAILS_V0_10.remove(storage, club_name);
+    }
+    Ok(())
+}
+
+/// 0.12.0 distributes rewards through reward indices, which need the
+/// total staked over all clubs
+fn migrate_total_staked_to_v0_12(storage: &mut dyn Storage) -> StdResult<()> {
+    let mut total_staked = Uint128::zero();
+    for item in CLUB_STAKING_TOTALS.range(storage, None, None, Order::Ascending) {
+        total_staked += item?.1;
+    }
+    TOTAL_STAKED.save(storage, &total_staked)
+}
+
+/// 0.13.0 keeps the club ranking in CLUB_LEADERBOARD instead of sorting
+/// the club totals on every call
+fn migrate_leaderboard_to_v0_13(storage: &mut dyn Storage) -> StdResult<()> {
+    let totals: StdResult<Vec<(Vec<u8>, Uint128)>> = CLUB_STAKING_TOTALS
+        .range(storage, None, None, Order::Ascending)
+        .collect();
+    for (club_name, total) in totals? {
+        let club_name = String::from_utf8(club_name)?;
+        CLUB_LEADERBOARD.save(storage, leaderboard_key(club_name, total), &total)?;
+    }
+    Ok(())
+}
+
+/// 0.14.0 only lets registered clubs be bought or staked on. Every club
+/// bought so far is registered as an active club created by the admin.
+fn migrate_club_registry_to_v0_14(storage: &mut dyn Storage, env: &Env) -> StdResult<()> {
+    let config = CONFIG.load(storage)?;
+    let club_names: Vec<String> = CLUB_OWNERSHIP_DETAILS
+        .keys(storage, None, None, Order::Ascending)
+        .map(String::from_utf8)
+        .collect::<Result<_, _>>()?;
+    for club_name in club_names {
+        CLUBS.save(
+            storage,
+            club_name.clone(),
+            &ClubInfo {
+                club_name: club_name.clone(),
+                display_name: club_name,
+                description: None,
+                logo: None,
+                status: ClubStatus::Active,
+                creator: config.admin_address.clone(),
+                created_at: env.block.time,
+            },
+        )?;
+    }
+    Ok(())
+}
+
+/// 0.15.0 keeps a reward balance per (club, previous owner) instead of
+/// only the latest previous owner of a club, and starts the ownership
+/// history of every club with its current owner.
+fn migrate_ownership_history_to_v0_15(storage: &mut dyn Storage) -> StdResult<()> {
+    let previous_owners: StdResult<Vec<(Vec<u8>, ClubPreviousOwnerDetails)>> =
+        CLUB_PREVIOUS_OWNER_DETAILS_V0_14
+            .range(storage, None, None, Order::Ascending)
+            .collect();
+    for (club_name, previous_owner) in previous_owners? {
+        CLUB_PREVIOUS_OWNER_DETAILS_V0_14.remove(storage, String::from_utf8(club_name)?);
+        CLUB_PREVIOUS_OWNER_DETAILS.save(
+            storage,
+            (
+                previous_owner.club_name.clone(),
+                previous_owner.previous_owner_address.clone(),
+            ),
+            &previous_owner,
+        )?;
+    }
+
+    let owners: StdResult<Vec<(Vec<u8>, ClubOwnershipDetails)>> = CLUB_OWNERSHIP_DETAILS
+        .range(storage, None, None, Order::Ascending)
+        .collect();
+    for (_, owner) in owners? {
+        append_ownership_record(
+            storage,
+            &owner.club_name,
+            owner.owner_address,
+            owner.start_timestamp,
+            owner.price_paid,
+            owner.reward_amount,
+        )?;
+    }
+    Ok(())
+}
+
+/// 0.16.0 looks up the clubs of an owner through OWNER_CLUBS
+fn migrate_owner_clubs_to_v0_16(storage: &mut dyn Storage) -> StdResult<()> {
+    let owners: StdResult<Vec<(Vec<u8>, ClubOwnershipDetails)>> = CLUB_OWNERSHIP_DETAILS
+        .range(storage, None, None, Order::Ascending)
+        .collect();
+    for (_, owner) in owners? {
+        OWNER_CLUBS.save(storage, (owner.owner_address, owner.club_name), &Empty {})?;
+    }
+    Ok(())
+}
+
+/// 0.17.0 keys every club by its canonical name. Clubs stored before names
+/// were normalized are moved to the canonical key; two stored names that
+/// map to the same club make the migration fail. Stored names are not held
+/// to the rules for new names, the ones breaking them are returned instead.
+fn migrate_club_names_to_v0_17(storage: &mut dyn Storage) -> Result<Vec<String>, ContractError> {
+    let collision = |club: &str, normalized: &str| ContractError::ClubNameCollision {
+        club: club.to_string(),
+        normalized: normalized.to_string(),
+    };
+
+    // Entries derived from the club totals and owners move before those do
+    let totals: StdResult<Vec<(Vec<u8>, Uint128)>> = CLUB_STAKING_TOTALS
+        .range(storage, None, None, Order::Ascending)
+        .collect();
+    for (club_name, total) in totals? {
+        let club_name = String::from_utf8(club_name).map_err(StdError::from)?;
+        let normalized = canonical_club_name(&club_name);
+        if normalized != club_name {
+            CLUB_LEADERBOARD.remove(storage, leaderboard_key(club_name, total));
+            CLUB_LEADERBOARD.save(storage, leaderboard_key(normalized, total), &total)?;
+        }
+    }
+    let owners: StdResult<Vec<(Vec<u8>, ClubOwnershipDetails)>> = CLUB_OWNERSHIP_DETAILS
+        .range(storage, None, None, Order::Ascending)
+        .collect();
+    for (_, owner) in owners? {
+        let normalized = canonical_club_name(&owner.club_name);
+        if normalized != owner.club_name {
+            OWNER_CLUBS.remove(storage, (owner.owner_address.clone(), owner.club_name));
+            OWNER_CLUBS.save(storage, (owner.owner_address, normalized), &Empty {})?;
+        }
+    }
+
+    normalize_club_keys(storage, &CLUBS, |club, name| club.club_name = name.to_string())?;
+    normalize_club_keys(storage, &CLUB_AUCTIONS, |auction, name| {
+        auction.club_name = name.to_string()
+    })?;
+    normalize_club_keys(storage, &CLUB_LISTINGS, |listing, name| {
+        listing.club_name = name.to_string()
+    })?;
+    normalize_club_keys(storage, &CLUB_OWNERSHIP_DETAILS, |owner, name| {
+        owner.club_name = name.to_string()
+    })?;
+    normalize_club_keys(storage, &CLUB_STAKING_TOTALS, |_, _| {})?;
+    normalize_club_keys(storage, &CLUB_REWARD_INDEX, |_, _| {})?;
+    normalize_club_keys(storage, &CLUB_OWNERSHIP_HISTORY_LENGTH, |_, _| {})?;
+    normalize_club_keys(storage, &CLUB_BONDING_DETAILS, |bonds, name| {
+        for bond in bonds.iter_mut() {
+            bond.club_name = name.to_string();
+        }
+    })?;
+
+    let stakes: StdResult<Vec<(Vec<u8>, ClubStakingDetails)>> = club_staking_details()
+        .range(storage, None, None, Order::Ascending)
+        .collect();
+    for (_, mut stake) in stakes? {
+        let normalized = canonical_club_name(&stake.club_name);
+        if normalized == stake.club_name {
+            continue;
+        }
+        let key = (normalized.clone(), stake.staker_address.clone());
+        if club_staking_details().may_load(storage, key.clone())?.is_some() {
+            return Err(collision(&stake.club_name, &normalized));
+        }
+        club_staking_details().remove(storage, (stake.club_name.clone(), stake.staker_address.clone()))?;
+        stake.club_name = normalized;
+        club_staking_details().save(storage, key, &stake)?;
+    }
+
+    let offers: StdResult<Vec<(Vec<u8>, ClubOffer)>> = CLUB_OFFERS
+        .range(storage, None, None, Order::Ascending)
+        .collect();
+    for (_, mut offer) in offers? {
+        let normalized = canonical_club_name(&offer.club_name);
+        if normalized == offer.club_name {
+            continue;
+        }
+        let key = (normalized.clone(), offer.bidder.to_string());
+        if CLUB_OFFERS.has(storage, key.clone()) {
+            return Err(collision(&offer.club_name, &normalized));
+        }
+        CLUB_OFFERS.remove(storage, (offer.club_name.clone(), offer.bidder.to_string()));
+        offer.club_name = normalized;
+        CLUB_OFFERS.save(storage, key, &offer)?;
+    }
+
+    let previous_owners: StdResult<Vec<(Vec<u8>, ClubPreviousOwnerDetails)>> =
+        CLUB_PREVIOUS_OWNER_DETAILS
+            .range(storage, None, None, Order::Ascending)
+            .collect();
+    for (_, mut previous_owner) in previous_owners? {
+        let normalized = canonical_club_name(&previous_owner.club_name);
+        if normalized == previous_owner.club_name {
+            continue;
+        }
+        let key = (normalized.clone(), previous_owner.previous_owner_address.clone());
+        if CLUB_PREVIOUS_OWNER_DETAILS.has(storage, key.clone()) {
+            return Err(collision(&previous_owner.club_name, &normalized));
+        }
+        CLUB_PREVIOUS_OWNER_DETAILS.remove(
+            storage,
+            (
+                previous_owner.club_name.clone(),
+                previous_owner.previous_owner_address.clone(),
+            ),
+        );
+        previous_owner.club_name = normalized;
+        CLUB_PREVIOUS_OWNER_DETAILS.save(storage, key, &previous_owner)?;
+    }
+
+    let history: StdResult<Vec<(Vec<u8>, ClubOwnershipRecord)>> = CLUB_OWNERSHIP_HISTORY
+        .range(storage, None, None, Order::Ascending)
+        .collect();
+    for (_, mut record) in history? {
+        let normalized = canonical_club_name(&record.club_name);
+        if normalized == record.club_name {
+            continue;
+        }
+        let key = (normalized.clone(), U64Key::from(record.sequence));
+        if CLUB_OWNERSHIP_HISTORY.has(storage, key.clone()) {
+            return Err(collision(&record.club_name, &normalized));
+        }
+        CLUB_OWNERSHIP_HISTORY.remove(storage, (record.club_name.clone(), U64Key::from(record.sequence)));
+        record.club_name = normalized;
+        CLUB_OWNERSHIP_HISTORY.save(storage, key, &record)?;
+    }
+
+    let club_names: Vec<String> = CLUBS
+        .keys(storage, None, None, Order::Ascending)
+        .map(String::from_utf8)
+        .collect::<Result<_, _>>()
+        .map_err(StdError::from)?;
+    Ok(club_names
+        .into_iter()
+        .filter(|club_name| normalize_club_name(club_name).is_err())
+        .collect())
+}
+
+/// 0.17.0 only pays rewards out of the tokens not held for users, which
+/// needs the total of the stakes, bonds, bids and offers held so far.
+/// Before 0.10.0 buying a club moved price credits between the wallets of
+/// buyer and seller, so the wallets are rebuilt from the stakes and bonds
+/// they stand for rather than trusted.
+fn migrate_escrowed_funds_to_v0_17(storage: &mut dyn Storage) -> StdResult<()> {
+    let wallets: Vec<Addr> = CONTRACT_WALLET
+        .keys(storage, None, None, Order::Ascending)
+        .map(|k| String::from_utf8(k).map(Addr::unchecked))
+        .collect::<Result<_, _>>()?;
+    for wallet in wallets {
+        CONTRACT_WALLET.remove(storage, &wallet);
+    }
+
+    let mut held: Vec<(String, Uint128)> = Vec::new();
+    for item in club_staking_details().range(storage, None, None, Order::Ascending) {
+        let stake = item?.1;
+        held.push((stake.staker_address, stake.staked_amount));
+    }
+    for item in CLUB_BONDING_DETAILS.range(storage, None, None, Order::Ascending) {
+        for bond in item?.1 {
+            held.push((bond.bonder_address, bond.bonded_amount));
+        }
+    }
+    let mut escrowed = Uint128::zero();
+    for (address, amount) in held {
+        if amount.is_zero() {
+            continue;
+        }
+        CONTRACT_WALLET.update(
+            storage,
+            &Addr::unchecked(address),
+            |balance: Option<Uint128>| -> StdResult<_> { Ok(balance.unwrap_or_default().checked_add(amount)?) },
+        )?;
+        escrowed = escrowed.checked_add(amount)?;
+    }
+
+    for item in CLUB_AUCTIONS.range(storage, None, None, Order::Ascending) {
+        if let Some(bid) = item?.1.highest_bid {
+            escrowed += bid.amount;
+        }
+    }
+    for item in CLUB_OFFERS.range(storage, None, None, Order::Ascending) {
+        escrowed += item?.1.amount;
+    }
+    ESCROWED_FUNDS.save(storage, &escrowed)
+}
+
+/// 0.17.0 pages through bonds by an id that stays with the bond. The bonds
+/// waiting so far are numbered in the order they were made.
+fn migrate_bond_ids_to_v0_17(storage: &mut dyn Storage) -> StdResult<()> {
+    let bonds: StdResult<Vec<(Vec<u8>, Vec<ClubBondingDetails>)>> = CLUB_BONDING_DETAILS
+        .range(storage, None, None, Order::Ascending)
+        .collect();
+    for (club_name, mut club_bonds) in bonds? {
+        let club_name = String::from_utf8(club_name)?;
+        for (bond_id, bond) in club_bonds.iter_mut().enumerate() {
+            bond.bond_id = bond_id as u64;
+        }
+        CLUB_BOND_SEQUENCE.save(storage, club_name.clone(), &(club_bonds.len() as u64))?;
+        CLUB_BONDING_DETAILS.save(storage, club_name, &club_bonds)?;
+    }
+    Ok(())
+}
+
+/// 0.17.0 finds the bonds and previous owner balances of an address
+/// through reverse indexes instead of scanning every club
+fn migrate_portfolio_indexes_to_v0_17(storage: &mut dyn Storage) -> StdResult<()> {
+    let bonds: StdResult<Vec<(Vec<u8>, Vec<ClubBondingDetails>)>> = CLUB_BONDING_DETAILS
+        .range(storage, None, None, Order::Ascending)
+        .collect();
+    for (_, club_bonds) in bonds? {
+        for bond in club_bonds {
+            BONDER_CLUBS.save(storage, (bond.bonder_address, bond.club_name), &Empty {})?;
+        }
+    }
+    let previous_owners: StdResult<Vec<(Vec<u8>, ClubPreviousOwnerDetails)>> =
+        CLUB_PREVIOUS_OWNER_DETAILS
+            .range(storage, None, None, Order::Ascending)
+            .collect();
+    for (_, previous_owner) in previous_owners? {
+        PREVIOUS_OWNER_CLUBS.save(
+            storage,
+            (previous_owner.previous_owner_address, previous_owner.club_name),
+            &Empty {},
+        )?;
+    }
+    Ok(())
+}
+
+/// Moves the entries of a map keyed by club name to the normalized name.
+/// fix_club_name updates a club name the value carries itself.
+fn normalize_club_keys<T>(
+    storage: &mut dyn Storage,
+    map: &Map<String, T>,
+    fix_club_name: impl Fn(&mut T, &str),
+) -> Result<(), ContractError>
+where
+    T: Serialize + DeserializeOwned,
+{
+    let entries: StdResult<Vec<(Vec<u8>, T)>> =
+        map.range(storage, None, None, Order::Ascending).collect();
+    for (club_name, mut value) in entries? {
+        let club_name = String::from_utf8(club_name).map_err(StdError::from)?;
+        let normalized = canonical_club_name(&club_name);
+        if normalized == club_name {
+            continue;
+        }
+        if map.has(storage, normalized.clone()) {
+            return Err(ContractError::ClubNameCollision {
+                club: club_name,
+                normalized,
+            });
+        }
+        map.remove(storage, club_name);
+        fix_club_name(&mut value, &normalized);
+        map.save(storage, normalized, &value)?;
+    }
+    Ok(())
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -76,34 +638,104 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::BuyAClub { buyer, seller, club_name } => {
-            buy_a_club(deps, env, info, buyer, seller, club_name, Uint128::from(CLUB_PRICE))
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::ProposeNewAdmin { address, expiry } => {
+            propose_new_admin(deps, env, info, address, expiry)
+        }
+        ExecuteMsg::AcceptAdmin {} => accept_admin(deps, env, info),
+        ExecuteMsg::CancelAdminProposal {} => cancel_admin_proposal(deps, env, info),
+        ExecuteMsg::GrantRole { address, role } => grant_role(deps, env, info, address, role),
+        ExecuteMsg::RevokeRole { address, role } => revoke_role(deps, env, info, address, role),
+        ExecuteMsg::UpdateConfig {
+            treasury_address,
+            protocol_fee_percentage,
+            early_withdrawal_penalty_percentage,
+            penalty_destination,
+            club_price,
+            club_buying_reward_amount,
+            club_staking_reward_amount,
+            club_locking_duration,
+            club_bonding_duration,
+            owner_reward_percentage,
+            winner_club_reward_percentage,
+            all_stakers_reward_percentage,
+            club_creation_fee,
+            auction_extension_duration,
+            pricing_model,
+            max_clubs_per_owner,
+        } => update_config(
+            deps,
+            env,
+            info,
+            treasury_address,
+            protocol_fee_percentage,
+            early_withdrawal_penalty_percentage,
+            penalty_destination,
+            club_price,
+            club_buying_reward_amount,
+            club_staking_reward_amount,
+            club_locking_duration,
+            club_bonding_duration,
+            owner_reward_percentage,
+            winner_club_reward_percentage,
+            all_stakers_reward_percentage,
+            club_creation_fee,
+            auction_extension_duration,
+            pricing_model,
+            max_clubs_per_owner,
+        ),
+        ExecuteMsg::CreateClub {
+            club_name,
+            display_name,
+            description,
+            logo,
+        } => create_club_as_admin(deps, env, info, club_name, display_name, description, logo),
+        ExecuteMsg::UpdateClubMetadata {
+            club_name,
+            display_name,
+            description,
+            logo,
+        } => update_club_metadata(deps, env, info, club_name, display_name, description, logo),
+        ExecuteMsg::SetClubStatus { club_name, status } => {
+            set_club_status(deps, env, info, club_name, status)
         }
         ExecuteMsg::ReleaseClub { owner, club_name } => {
             release_club(deps, env, info, owner, club_name)
         }
-        ExecuteMsg::StakeOnAClub {
-            staker,
+        ExecuteMsg::StartAuction {
             club_name,
-            amount,
-        } => stake_on_a_club(deps, env, info, staker, club_name, amount),
+            reserve_price,
+            min_bid_increment,
+            duration,
+        } => start_auction(deps, env, info, club_name, reserve_price, min_bid_increment, duration),
+        ExecuteMsg::SettleAuction { club_name } => settle_auction(deps, env, club_name),
+        ExecuteMsg::ListClub {
+            club_name,
+            asking_price,
+            expiry,
+        } => list_club(deps, env, info, club_name, asking_price, expiry),
+        ExecuteMsg::CancelListing { club_name } => cancel_listing(deps, env, info, club_name),
+        ExecuteMsg::AcceptOffer { club_name, bidder } => {
+            accept_offer(deps, env, info, club_name, bidder)
+        }
+        ExecuteMsg::WithdrawOffer { club_name } => withdraw_offer(deps, env, info, club_name),
         ExecuteMsg::ClaimOwnerRewards {
             owner,
             club_name,
             amount,
-        } => claim_owner_rewards(deps, info, owner, club_name, amount),
+        } => claim_owner_rewards(deps, env, info, owner, club_name, amount),
         ExecuteMsg::ClaimPreviousOwnerRewards {
             previous_owner,
             club_name,
             amount,
-        } => claim_previous_owner_rewards(deps, info, previous_owner, club_name, amount),
+        } => claim_previous_owner_rewards(deps, env, info, previous_owner, club_name, amount),
         ExecuteMsg::StakeWithdrawFromAClub {
             staker,
             club_name,
             amount,
             immediate_withdrawal,
         } => withdraw_stake_from_a_club(deps, env, info, staker, club_name, amount, immediate_withdrawal),
-        ExecuteMsg::SetRewardAmount { amount } => set_reward_amount(deps, info, amount),
+        ExecuteMsg::SetRewardAmount { amount } => set_reward_amount(deps, env, info, amount),
         ExecuteMsg::CalculateAndDistributeRewards {} => {
             calculate_and_distribute_rewards(deps, env, info)
         }
@@ -111,10 +743,11 @@ pub fn execute(
             staker,
             club_name,
             amount,
-        } => claim_rewards(deps, info, staker, club_name, amount),
-        ExecuteMsg::PeriodicallyRefundStakeouts {} => {
-            periodically_refund_stakeouts(deps, env, info)
+        } => claim_rewards(deps, env, info, staker, club_name, amount),
+        ExecuteMsg::PeriodicallyRefundStakeouts { start_after, limit } => {
+            periodically_refund_stakeouts(deps, env, info, start_after, limit)
         }
+        ExecuteMsg::ClaimUnbonded { club_name } => claim_unbonded(deps, env, info, club_name),
         ExecuteMsg::IncreaseAllowance {
             spender,
             amount,
@@ -140,242 +773,1709 @@ pub fn execute(
     }
 }
 
-fn claim_previous_owner_rewards(
+fn propose_new_admin(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: String,
+    expiry: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin_address {
+        return Err(ContractError::Unauthorized {});
+    }
+    let expiry = expiry.unwrap_or_default();
+    if expiry.is_expired(&env.block) {
+        return Err(ContractError::Expired {});
+    }
+    let new_admin = deps.api.addr_validate(&address)?;
+    PENDING_ADMIN.save(
+        deps.storage,
+        &PendingAdmin {
+            address: new_admin.clone(),
+            expiry,
+        },
+    )?;
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "propose_new_admin"),
+            attr("admin", info.sender.clone()),
+            attr("pending_admin", new_admin.clone()),
+        ])
+        .add_event(
+            new_event("admin_proposed", &env)
+                .add_attribute("admin", info.sender)
+                .add_attribute("pending_admin", new_admin),
+        ))
+}
+
+fn accept_admin(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let pending_admin = match PENDING_ADMIN.may_load(deps.storage)? {
+        Some(pending_admin) => pending_admin,
+        None => {
+            return Err(ContractError::NoPendingAdmin {});
+        }
+    };
+    if pending_admin.address != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if pending_admin.expiry.is_expired(&env.block) {
+        return Err(ContractError::Expired {});
+    }
+
+    let mut config = CONFIG.load(deps.storage)?;
+    let previous_admin = config.admin_address;
+    config.admin_address = pending_admin.address;
+    CONFIG.save(deps.storage, &config)?;
+    PENDING_ADMIN.remove(deps.storage);
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "accept_admin"),
+            attr("previous_admin", previous_admin.clone()),
+            attr("admin", info.sender.clone()),
+        ])
+        .add_event(
+            new_event("admin_transferred", &env)
+                .add_attribute("previous_admin", previous_admin)
+                .add_attribute("admin", info.sender),
+        ))
+}
+
+fn cancel_admin_proposal(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin_address {
+        return Err(ContractError::Unauthorized {});
+    }
+    PENDING_ADMIN.remove(deps.storage);
+    Ok(Response::new()
+        .add_attribute("action", "cancel_admin_proposal")
+        .add_event(new_event("admin_proposal_cancelled", &env).add_attribute("admin", info.sender)))
+}
+
+fn grant_role(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: String,
+    role: Role,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin_address {
+        return Err(ContractError::Unauthorized {});
+    }
+    let addr = deps.api.addr_validate(&address)?;
+    ROLES.update(deps.storage, &addr, |roles| -> StdResult<_> {
+        let mut roles = roles.unwrap_or_default();
+        if !roles.contains(&role) {
+            roles.push(role.clone());
+        }
+        Ok(roles)
+    })?;
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "grant_role"),
+            attr("address", addr.clone()),
+            attr("role", role.to_string()),
+        ])
+        .add_event(
+            new_event("role_granted", &env)
+                .add_attribute("address", addr)
+                .add_attribute("role", role.to_string()),
+        ))
+}
+
+fn revoke_role(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: String,
+    role: Role,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin_address {
+        return Err(ContractError::Unauthorized {});
+    }
+    let addr = deps.api.addr_validate(&address)?;
+    let mut roles = ROLES.may_load(deps.storage, &addr)?.unwrap_or_default();
+    roles.retain(|r| *r != role);
+    if roles.is_empty() {
+        ROLES.remove(deps.storage, &addr);
+    } else {
+        ROLES.save(deps.storage, &addr, &roles)?;
+    }
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "revoke_role"),
+            attr("address", addr.clone()),
+            attr("role", role.to_string()),
+        ])
+        .add_event(
+            new_event("role_revoked", &env)
+                .add_attribute("address", addr)
+                .add_attribute("role", role.to_string()),
+        ))
+}
+
+/// Privileged operations are open to the admin and to holders of the given role
+fn ensure_admin_or_role(
+    storage: &dyn Storage,
+    config: &Config,
+    sender: &Addr,
+    role: Role,
+) -> Result<(), ContractError> {
+    if *sender == config.admin_address {
+        return Ok(());
+    }
+    let roles = ROLES.may_load(storage, sender)?.unwrap_or_default();
+    if roles.contains(&role) {
+        return Ok(());
+    }
+    Err(ContractError::Unauthorized {})
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_config(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    treasury_address: Option<String>,
+    protocol_fee_percentage: Option<Uint128>,
+    early_withdrawal_penalty_percentage: Option<Uint128>,
+    penalty_destination: Option<PenaltyDestination>,
+    club_price: Option<Uint128>,
+    club_buying_reward_amount: Option<Uint128>,
+    club_staking_reward_amount: Option<Uint128>,
+    club_locking_duration: Option<u64>,
+    club_bonding_duration: Option<u64>,
+    owner_reward_percentage: Option<Uint128>,
+    winner_club_reward_percentage: Option<Uint128>,
+    all_stakers_reward_percentage: Option<Uint128>,
+    club_creation_fee: Option<Uint128>,
+    auction_extension_duration: Option<u64>,
+    pricing_model: Option<PricingModel>,
+    max_clubs_per_owner: Option<u32>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin_address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(treasury_address) = treasury_address {
+        config.treasury_address = deps.api.addr_validate(&treasury_address)?;
+    }
+    if let Some(protocol_fee_percentage) = protocol_fee_percentage {
+        config.protocol_fee_percentage = protocol_fee_percentage;
+    }
+    if let Some(early_withdrawal_penalty_percentage) = early_withdrawal_penalty_percentage {
+        config.early_withdrawal_penalty_percentage = early_withdrawal_penalty_percentage;
+    }
+    if let Some(penalty_destination) = penalty_destination {
+        config.penalty_destination = penalty_destination;
+    }
+    if let Some(club_price) = club_price {
+        config.club_price = club_price;
+    }
+    if let Some(club_buying_reward_amount) = club_buying_reward_amount {
+        config.club_buying_reward_amount = club_buying_reward_amount;
+    }
+    if let Some(club_staking_reward_amount) = club_staking_reward_amount {
+        config.club_staking_reward_amount = club_staking_reward_amount;
+    }
+    if let Some(club_locking_duration) = club_locking_duration {
+        config.club_locking_duration = club_locking_duration;
+    }
+    if let Some(club_bonding_duration) = club_bonding_duration {
+        config.club_bonding_duration = club_bonding_duration;
+    }
+    if let Some(owner_reward_percentage) = owner_reward_percentage {
+        config.owner_reward_percentage = owner_reward_percentage;
+    }
+    if let Some(winner_club_reward_percentage) = winner_club_reward_percentage {
+        config.winner_club_reward_percentage = winner_club_reward_percentage;
+    }
+    if let Some(all_stakers_reward_percentage) = all_stakers_reward_percentage {
+        config.all_stakers_reward_percentage = all_stakers_reward_percentage;
+    }
+    if let Some(club_creation_fee) = club_creation_fee {
+        config.club_creation_fee = club_creation_fee;
+    }
+    if let Some(auction_extension_duration) = auction_extension_duration {
+        config.auction_extension_duration = auction_extension_duration;
+    }
+    if let Some(pricing_model) = pricing_model {
+        config.pricing_model = pricing_model;
+    }
+    if let Some(max_clubs_per_owner) = max_clubs_per_owner {
+        config.max_clubs_per_owner = max_clubs_per_owner;
+    }
+    validate_config(&config)?;
+    CONFIG.save(deps.storage, &config)?;
+    Ok(Response::new()
+        .add_attribute("action", "update_config")
+        .add_event(new_event("config_updated", &env).add_attribute("admin", info.sender)))
+}
+
+fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    // Only our own token may be used to stake or buy. Any other cw20
+    // contract could claim to have sent an arbitrary amount.
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.cw20_token_address {
+        return Err(ContractError::Unauthorized {});
+    }
+    match from_binary(&cw20_msg.msg)? {
+        ReceiveMsg::StakeOnAClub { club_name } => {
+            stake_on_a_club(deps, env, cw20_msg.sender, club_name, cw20_msg.amount)
+        }
+        ReceiveMsg::BuyAClub { seller, club_name } => {
+            buy_a_club(deps, env, cw20_msg.sender, seller, club_name, cw20_msg.amount)
+        }
+        ReceiveMsg::PlaceBid { club_name } => {
+            place_bid(deps, env, cw20_msg.sender, club_name, cw20_msg.amount)
+        }
+        ReceiveMsg::MakeOffer { club_name, expiry } => {
+            make_offer(deps, env, cw20_msg.sender, club_name, cw20_msg.amount, expiry)
+        }
+        ReceiveMsg::CreateClub {
+            club_name,
+            display_name,
+            description,
+            logo,
+        } => {
+            if cw20_msg.amount < config.club_creation_fee {
+                return Err(ContractError::InsufficientFunds {
+                    required: config.club_creation_fee,
+                    sent: cw20_msg.amount,
+                });
+            }
+            let creator = deps.api.addr_validate(&cw20_msg.sender)?;
+            let excess = cw20_msg.amount - config.club_creation_fee;
+            let mut messages = Vec::new();
+            if config.club_creation_fee > Uint128::zero() {
+                messages.push(transfer_tokens_msg(
+                    &config,
+                    config.treasury_address.clone(),
+                    config.club_creation_fee,
+                )?);
+            }
+            if excess > Uint128::zero() {
+                messages.push(transfer_tokens_msg(&config, creator.clone(), excess)?);
+            }
+            let response = create_club(deps, env, creator, club_name, display_name, description, logo)?;
+            Ok(response.add_messages(messages))
+        }
+    }
+}
+
+fn create_club_as_admin(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    club_name: String,
+    display_name: String,
+    description: Option<String>,
+    logo: Option<Logo>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin_address {
+        return Err(ContractError::Unauthorized {});
+    }
+    create_club(deps, env, info.sender, club_name, display_name, description, logo)
+}
+
+fn create_club(
+    deps: DepsMut,
+    env: Env,
+    creator: Addr,
+    club_name: String,
+    display_name: String,
+    description: Option<String>,
+    logo: Option<Logo>,
+) -> Result<Response, ContractError> {
+    let club_name = normalize_club_name(&club_name)?;
+    if CLUBS.has(deps.storage, club_name.clone()) {
+        return Err(ContractError::ClubAlreadyExists { club: club_name });
+    }
+    verify_display_name(&display_name)?;
+    if let Some(description) = &description {
+        verify_description(description)?;
+    }
+    if let Some(logo) = &logo {
+        verify_logo(logo)?;
+    }
+    CLUBS.save(
+        deps.storage,
+        club_name.clone(),
+        &ClubInfo {
+            club_name: club_name.clone(),
+            display_name,
+            description,
+            logo,
+            status: ClubStatus::Active,
+            creator: creator.clone(),
+            created_at: env.block.time,
+        },
+    )?;
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "create_club"),
+            attr("club", club_name.clone()),
+            attr("address", creator.clone()),
+        ])
+        .add_event(
+            new_event("club_created", &env)
+                .add_attribute("club", club_name)
+                .add_attribute("address", creator),
+        ))
+}
+
+fn update_club_metadata(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    club_name: String,
+    display_name: Option<String>,
+    description: Option<Option<String>>,
+    logo: Option<Logo>,
+) -> Result<Response, ContractError> {
+    let club_name = normalize_club_name(&club_name)?;
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin_address {
+        return Err(ContractError::Unauthorized {});
+    }
+    let mut club = load_club(deps.storage, &club_name)?;
+    if let Some(display_name) = display_name {
+        verify_display_name(&display_name)?;
+        club.display_name = display_name;
+    }
+    if let Some(description) = description {
+        if let Some(description) = &description {
+            verify_description(description)?;
+        }
+        club.description = description;
+    }
+    if let Some(logo) = logo {
+        verify_logo(&logo)?;
+        club.logo = Some(logo);
+    }
+    CLUBS.save(deps.storage, club_name.clone(), &club)?;
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "update_club_metadata"),
+            attr("club", club_name.clone()),
+        ])
+        .add_event(new_event("club_metadata_updated", &env).add_attribute("club", club_name)))
+}
+
+fn set_club_status(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    club_name: String,
+    status: ClubStatus,
+) -> Result<Response, ContractError> {
+    let club_name = normalize_club_name(&club_name)?;
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin_address {
+        return Err(ContractError::Unauthorized {});
+    }
+    let mut club = load_club(deps.storage, &club_name)?;
+    if club.status == ClubStatus::Retired && status != ClubStatus::Retired {
+        return Err(ContractError::ClubRetired { club: club_name });
+    }
+    club.status = status;
+    CLUBS.save(deps.storage, club_name.clone(), &club)?;
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "set_club_status"),
+            attr("club", club_name.clone()),
+            attr("status", club.status.to_string()),
+        ])
+        .add_event(
+            new_event("club_status_changed", &env)
+                .add_attribute("club", club_name)
+                .add_attribute("status", club.status.to_string()),
+        ))
+}
+
+fn load_club(storage: &dyn Storage, club_name: &str) -> Result<ClubInfo, ContractError> {
+    CLUBS
+        .may_load(storage, club_name.to_string())?
+        .ok_or_else(|| ContractError::ClubNotFound {
+            club: club_name.to_string(),
+        })
+}
+
+/// Buying and staking are only open on registered, active clubs
+fn ensure_club_is_active(storage: &dyn Storage, club_name: &str) -> Result<(), ContractError> {
+    let club = load_club(storage, club_name)?;
+    if club.status != ClubStatus::Active {
+        return Err(ContractError::ClubNotActive {
+            club: club.club_name,
+            status: club.status,
+        });
+    }
+    Ok(())
+}
+
+const MIN_CLUB_NAME_LENGTH: usize = 3;
+const MAX_CLUB_NAME_LENGTH: usize = 32;
+
+/// Club names are storage keys, so every name coming in is brought into its
+/// canonical form first: surrounding whitespace trimmed, inner whitespace
+/// collapsed to single spaces and letters upper cased. "chelsea " and
+/// "Chelsea" both end up as "CHELSEA".
+pub(crate) fn canonical_club_name(club_name: &str) -> String {
+    club_name.split_whitespace().collect::<Vec<_>>().join(" ").to_uppercase()
+}
+
+/// canonical_club_name for new names, which also have to be of a valid
+/// length and character set
+pub(crate) fn normalize_club_name(club_name: &str) -> Result<String, ContractError> {
+    let canonical = canonical_club_name(club_name);
+    let invalid = |reason: String| ContractError::InvalidClubName {
+        club: club_name.to_string(),
+        reason,
+    };
+    if canonical.len() < MIN_CLUB_NAME_LENGTH || canonical.len() > MAX_CLUB_NAME_LENGTH {
+        return Err(invalid(format!(
+            "must be between {} and {} characters long",
+            MIN_CLUB_NAME_LENGTH, MAX_CLUB_NAME_LENGTH
+        )));
+    }
+    if let Some(c) = canonical
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || *c == ' ' || *c == '-' || *c == '_'))
+    {
+        return Err(invalid(format!(
+            "may only contain letters, digits, spaces, '-' and '_', found '{}'",
+            c
+        )));
+    }
+    Ok(canonical)
+}
+
+/// normalize_club_name for the query entry points
+fn normalize_queried_club_name(club_name: &str) -> StdResult<String> {
+    normalize_club_name(club_name).map_err(|err| StdError::generic_err(err.to_string()))
+}
+
+const LOGO_SIZE_CAP: usize = 5 * 1024;
+const DISPLAY_NAME_CAP: usize = 64;
+const DESCRIPTION_CAP: usize = 1024;
+
+/// Club metadata is stored on chain as well, so names and descriptions are capped too
+fn verify_display_name(display_name: &str) -> Result<(), ContractError> {
+    if display_name.len() > DISPLAY_NAME_CAP {
+        return Err(ContractError::DisplayNameTooLong {});
+    }
+    Ok(())
+}
+
+fn verify_description(description: &str) -> Result<(), ContractError> {
+    if description.len() > DESCRIPTION_CAP {
+        return Err(ContractError::DescriptionTooLong {});
+    }
+    Ok(())
+}
+
+/// Embedded logos are stored on chain, so their size is capped
+fn verify_logo(logo: &Logo) -> Result<(), ContractError> {
+    if let Logo::Embedded(EmbeddedLogo::Svg(data) | EmbeddedLogo::Png(data)) = logo {
+        if data.len() > LOGO_SIZE_CAP {
+            return Err(ContractError::LogoTooBig {});
+        }
+    }
+    Ok(())
+}
+
+fn claim_previous_owner_rewards(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    previous_owner: String,
+    club_name: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let club_name = normalize_club_name(&club_name)?;
+    let previous_owner_addr = deps.api.addr_validate(&previous_owner)?;
+    //Check if withdrawer is same as invoker
+    if previous_owner_addr != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let key = (club_name.clone(), previous_owner_addr.to_string());
+    let mut previous_owner_detail = CLUB_PREVIOUS_OWNER_DETAILS
+        .may_load(deps.storage, key.clone())?
+        .ok_or(ContractError::InsufficientRewards {
+            requested: amount,
+            available: Uint128::zero(),
+        })?;
+    if amount > previous_owner_detail.reward_amount {
+        return Err(ContractError::InsufficientRewards {
+            requested: amount,
+            available: previous_owner_detail.reward_amount,
+        });
+    }
+
+    // Now save the previous ownership details
+    previous_owner_detail.reward_amount -= amount;
+    CLUB_PREVIOUS_OWNER_DETAILS.save(deps.storage, key, &previous_owner_detail)?;
+
+    return pay_out_reward(
+        deps.as_ref(),
+        &env,
+        "claim_previous_owner_rewards",
+        previous_owner_addr,
+        club_name,
+        amount,
+    );
+}
+
+fn claim_owner_rewards (
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    club_name: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let club_name = normalize_club_name(&club_name)?;
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    //Check if withdrawer is same as invoker
+    if owner_addr != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let mut owner_detail = load_owned_club(deps.storage, &club_name, &owner_addr)?;
+    if amount > owner_detail.reward_amount {
+        return Err(ContractError::InsufficientRewards {
+            requested: amount,
+            available: owner_detail.reward_amount,
+        });
+    }
+
+    // Now save the ownership details
+    owner_detail.reward_amount -= amount;
+    CLUB_OWNERSHIP_DETAILS.save(deps.storage, club_name.clone(), &owner_detail)?;
+
+    return pay_out_reward(
+        deps.as_ref(),
+        &env,
+        "claim_owner_rewards",
+        owner_addr,
+        club_name,
+        amount,
+    );
+}
+
+// settings for the clubs swept by a PeriodicallyRefundStakeouts call
+const DEFAULT_REFUND_CLUBS: u32 = 10;
+const MAX_REFUND_CLUBS: u32 = 30;
+
+fn periodically_refund_stakeouts(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    //capture the current system time
+    let now = env.block.time;
+
+    //Check if the sender is the admin or a refund keeper
+    let config = CONFIG.load(deps.storage)?;
+    ensure_admin_or_role(deps.storage, &config, &info.sender, Role::RefundKeeper)?;
+
+    // Matured bond amounts are batched per bonder so that each of them
+    // gets a single transfer
+    let mut refunds: BTreeMap<String, Uint128> = BTreeMap::new();
+    let mut bonds_settled = 0u64;
+    // A bond its bonder's wallet does not cover is left in place rather
+    // than failing the refunds of everyone else
+    let mut wallets: BTreeMap<String, Uint128> = BTreeMap::new();
+    let mut bonds_skipped = 0u64;
+
+    // Fetch the bonding details of one page of clubs
+    let limit = limit.unwrap_or(DEFAULT_REFUND_CLUBS).min(MAX_REFUND_CLUBS) as usize;
+    let start = start_after
+        .map(|club_name| normalize_club_name(&club_name))
+        .transpose()?
+        .map(Bound::exclusive);
+    let all_clubs: Vec<String> = CLUB_BONDING_DETAILS
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(String::from_utf8)
+        .collect::<Result<_, _>>()
+        .map_err(StdError::from)?;
+    let last_club = if all_clubs.len() == limit {
+        all_clubs.last().cloned()
+    } else {
+        None
+    };
+    for club_name in all_clubs {
+        let mut all_bonds = Vec::new();
+        let bonding_details = CLUB_BONDING_DETAILS.load(deps.storage, club_name.clone())?;
+        let mut settled_bonders = Vec::new();
+        for bond in bonding_details {
+            if !is_bond_matured(&bond, now) {
+                all_bonds.push(bond);
+                continue;
+            }
+            let wallet = match wallets.get(&bond.bonder_address) {
+                Some(wallet) => *wallet,
+                None => CONTRACT_WALLET
+                    .may_load(deps.storage, &Addr::unchecked(bond.bonder_address.clone()))?
+                    .unwrap_or_default(),
+            };
+            match wallet.checked_sub(bond.bonded_amount) {
+                Ok(remaining) => {
+                    wallets.insert(bond.bonder_address.clone(), remaining);
+                    *refunds.entry(bond.bonder_address.clone()).or_default() += bond.bonded_amount;
+                    settled_bonders.push(bond.bonder_address);
+                    bonds_settled += 1;
+                }
+                Err(_) => {
+                    all_bonds.push(bond);
+                    bonds_skipped += 1;
+                }
+            }
+        }
+        for bonder in settled_bonders {
+            release_bonder_club(deps.storage, bonder, &club_name, &all_bonds);
+        }
+        CLUB_BONDING_DETAILS.save(deps.storage, club_name, &all_bonds)?;
+    }
+
+    let mut total_refunded = Uint128::zero();
+    let mut messages = Vec::new();
+    let mut events = Vec::new();
+    for (bonder, amount) in refunds {
+        let bonder_addr = deps.api.addr_validate(&bonder)?;
+        CONTRACT_WALLET.update(
+            deps.storage,
+            &bonder_addr,
+            |balance: Option<Uint128>| -> StdResult<_> {
+                Ok(balance.unwrap_or_default().checked_sub(amount)?)
+            },
+        )?;
+        release_funds(deps.storage, amount)?;
+        messages.push(transfer_tokens_msg(&config, bonder_addr.clone(), amount)?);
+        events.push(
+            new_event("bond_refunded", &env)
+                .add_attribute("address", bonder_addr)
+                .add_attribute("amount", amount),
+        );
+        total_refunded += amount;
+    }
+
+    let mut response = Response::new()
+        .add_messages(messages)
+        .add_events(events)
+        .add_attributes(vec![
+            attr("action", "periodically_refund_stakeouts"),
+            attr("bonds_settled", bonds_settled.to_string()),
+            attr("bonds_skipped", bonds_skipped.to_string()),
+            attr("total_refunded", total_refunded),
+        ]);
+    // More clubs may be waiting, the next call starts after this one
+    if let Some(last_club) = last_club {
+        response = response.add_attribute("last_club", last_club);
+    }
+    return Ok(response);
+}
+
+fn claim_unbonded(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    club_name: Option<String>,
+) -> Result<Response, ContractError> {
+    let club_name = club_name.map(|club_name| normalize_club_name(&club_name)).transpose()?;
+    //capture the current system time
+    let now = env.block.time;
+
+    // Either the given club or every club the sender has bonds in
+    let clubs: Vec<String> = match club_name {
+        Some(club_name) => vec![club_name],
+        None => staker_clubs(deps.storage, &BONDER_CLUBS, &info.sender)?,
+    };
+
+    let mut bonds_settled = 0u64;
+    let mut refund_amount = Uint128::zero();
+    for club_name in clubs {
+        let bonding_details = CLUB_BONDING_DETAILS
+            .may_load(deps.storage, club_name.clone())?
+            .unwrap_or_default();
+        let mut all_bonds = Vec::new();
+        for bond in bonding_details {
+            if bond.bonder_address == info.sender && is_bond_matured(&bond, now) {
+                refund_amount += bond.bonded_amount;
+                bonds_settled += 1;
+            } else {
+                all_bonds.push(bond);
+            }
+        }
+        release_bonder_club(deps.storage, info.sender.to_string(), &club_name, &all_bonds);
+        CLUB_BONDING_DETAILS.save(deps.storage, club_name, &all_bonds)?;
+    }
+
+    if refund_amount == Uint128::zero() {
+        return Err(ContractError::NoMaturedBonds {});
+    }
+
+    CONTRACT_WALLET.update(
+        deps.storage,
+        &info.sender,
+        |balance: Option<Uint128>| -> StdResult<_> {
+            Ok(balance.unwrap_or_default().checked_sub(refund_amount)?)
+        },
+    )?;
+    release_funds(deps.storage, refund_amount)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    return Ok(Response::new()
+        .add_message(transfer_tokens_msg(&config, info.sender.clone(), refund_amount)?)
+        .add_event(
+            new_event("bond_refunded", &env)
+                .add_attribute("address", info.sender.clone())
+                .add_attribute("amount", refund_amount),
+        )
+        .add_attributes(vec![
+            attr("action", "claim_unbonded"),
+            attr("address", info.sender),
+            attr("bonds_settled", bonds_settled.to_string()),
+            attr("amount", refund_amount),
+        ]));
+}
+
+/// Starts a typed event for indexers. Every event carries the block time
+/// of the state change it reports.
+fn new_event(kind: &str, env: &Env) -> Event {
+    Event::new(kind).add_attribute("timestamp", env.block.time.seconds().to_string())
+}
+
+/// A bond can be refunded once its bonding duration has elapsed
+fn is_bond_matured(bond: &ClubBondingDetails, now: Timestamp) -> bool {
+    now.minus_seconds(bond.bonding_duration) >= bond.bonding_start_timestamp
+}
+
+fn buy_a_club(
+    deps: DepsMut,
+    env: Env,
+    buyer: String,
+    seller: String,
+    club_name: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let club_name = normalize_club_name(&club_name)?;
+    let buyer_addr = deps.api.addr_validate(&buyer)?;
+    let listing = load_listing(deps.storage, &env.block, &club_name)?;
+    let ownership_details;
+    let ownership_details_result = CLUB_OWNERSHIP_DETAILS.may_load(deps.storage, club_name.clone());
+    match ownership_details_result {
+        Ok(od) => {
+            ownership_details = od;
+        }
+        Err(e) => {
+            return Err(ContractError::Std(StdError::from(e)));
+        }
+    }
+    ensure_club_is_purchasable(deps.storage, &club_name, ownership_details.as_ref())?;
+
+    if let Some(owner) = &ownership_details {
+        if owner.owner_address != seller {
+            return Err(ContractError::NotClubOwner {
+                club: club_name,
+                address: seller,
+            });
+        }
+    } else if seller != "".to_string() {
+        return Err(ContractError::NotClubOwner {
+            club: club_name,
+            address: seller,
+        });
+    }
+
+    // A listed club sells at the asking price of its owner
+    let config = CONFIG.load(deps.storage)?;
+    ensure_below_club_limit(deps.storage, &config, &buyer_addr)?;
+    let price = match listing {
+        Some(listing) => listing.asking_price,
+        None => floor_price(deps.storage, &config, &club_name, ownership_details.as_ref())?,
+    };
+    if amount < price {
+        return Err(ContractError::InsufficientFunds {
+            required: price,
+            sent: amount,
+        });
+    }
+    CLUB_LISTINGS.remove(deps.storage, club_name.clone());
+
+    let (mut transfers, protocol_fee) = transfer_club_ownership(
+        deps,
+        &env,
+        &config,
+        club_name.clone(),
+        &buyer_addr,
+        ownership_details,
+        price,
+    )?;
+
+    // Anything sent above the price goes back to the buyer
+    transfers.push((buyer_addr, amount - price));
+
+    let mut messages = Vec::new();
+    for (recipient, transfer_amount) in transfers {
+        if transfer_amount > Uint128::zero() {
+            messages.push(transfer_tokens_msg(&config, recipient, transfer_amount)?);
+        }
+    }
+    return Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![
+            attr("action", "buy_a_club"),
+            attr("club", club_name.clone()),
+            attr("address", buyer.clone()),
+            attr("amount", price),
+        ])
+        .add_event(
+            new_event("club_bought", &env)
+                .add_attribute("club", club_name)
+                .add_attribute("address", buyer)
+                .add_attribute("seller", seller)
+                .add_attribute("amount", price)
+                .add_attribute("protocol_fee", protocol_fee),
+        ));
+}
+
+/// Config.max_clubs_per_owner caps how many clubs the address may hold
+fn ensure_below_club_limit(
+    storage: &dyn Storage,
+    config: &Config,
+    owner: &Addr,
+) -> Result<(), ContractError> {
+    if config.max_clubs_per_owner == 0 {
+        return Ok(());
+    }
+    let owned = OWNER_CLUBS
+        .prefix(owner.to_string())
+        .keys(storage, None, None, Order::Ascending)
+        .take(config.max_clubs_per_owner as usize)
+        .count();
+    if owned >= config.max_clubs_per_owner as usize {
+        return Err(ContractError::TooManyClubs {
+            owner: owner.to_string(),
+            max: config.max_clubs_per_owner,
+        });
+    }
+    Ok(())
+}
+
+fn query_owned_clubs(deps: Deps, owner: String) -> StdResult<OwnedClubsResponse> {
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    let club_names: Vec<String> = OWNER_CLUBS
+        .prefix(owner_addr.to_string())
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(String::from_utf8)
+        .collect::<Result<_, _>>()?;
+    let clubs: StdResult<Vec<ClubOwnershipDetails>> = club_names
+        .into_iter()
+        .map(|club_name| CLUB_OWNERSHIP_DETAILS.load(deps.storage, club_name))
+        .collect();
+    Ok(OwnedClubsResponse {
+        owner: owner_addr.to_string(),
+        clubs: clubs?,
+    })
+}
+
+/// Lowest price the club can change hands for under Config.pricing_model,
+/// given its current ownership
+fn floor_price(
+    storage: &dyn Storage,
+    config: &Config,
+    club_name: &str,
+    ownership: Option<&ClubOwnershipDetails>,
+) -> Result<Uint128, ContractError> {
+    match &config.pricing_model {
+        PricingModel::Fixed => Ok(config.club_price),
+        PricingModel::Dynamic {
+            resale_multiplier,
+            stake_multiplier,
+        } => {
+            // The price compounds with every resale, so it can outgrow Uint128
+            let overflow = || ContractError::PriceOverflow {
+                club: club_name.to_string(),
+            };
+            let resale_price = match ownership {
+                Some(owner) => checked_mul_decimal(owner.price_paid, *resale_multiplier)
+                    .ok_or_else(overflow)?,
+                None => Uint128::zero(),
+            };
+            let total_staked = CLUB_STAKING_TOTALS
+                .may_load(storage, club_name.to_string())?
+                .unwrap_or_default();
+            let stake_price =
+                checked_mul_decimal(total_staked, *stake_multiplier).ok_or_else(overflow)?;
+            config
+                .club_price
+                .max(resale_price)
+                .checked_add(stake_price)
+                .map_err(|_| overflow())
+        }
+    }
+}
+
+/// amount * ratio, None if the result does not fit in a Uint128
+fn checked_mul_decimal(amount: Uint128, ratio: Decimal) -> Option<Uint128> {
+    let product = amount.full_mul(ratio.numerator()) / Uint256::from(ratio.denominator());
+    Uint128::try_from(product).ok()
+}
+
+/// The checks a club has to pass before buy_a_club sells it, shared with
+/// the price quote
+fn ensure_club_is_purchasable(
+    storage: &dyn Storage,
+    club_name: &str,
+    ownership: Option<&ClubOwnershipDetails>,
+) -> Result<(), ContractError> {
+    ensure_club_is_active(storage, club_name)?;
+    if CLUB_AUCTIONS.has(storage, club_name.to_string()) {
+        return Err(ContractError::ClubInAuction {
+            club: club_name.to_string(),
+        });
+    }
+    if let Some(owner) = ownership {
+        if !owner.owner_released {
+            return Err(ContractError::ClubNotReleased {
+                club: club_name.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn query_club_price(deps: Deps, env: Env, club_name: String) -> StdResult<ClubPriceResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let ownership = CLUB_OWNERSHIP_DETAILS.may_load(deps.storage, club_name.clone())?;
+    // An expired listing takes the club off sale, see load_listing
+    let listing = CLUB_LISTINGS.may_load(deps.storage, club_name.clone())?;
+    let listing_expired = matches!(&listing, Some(listing) if listing.expiry.is_expired(&env.block));
+    let purchasable = !listing_expired
+        && ensure_club_is_purchasable(deps.storage, &club_name, ownership.as_ref()).is_ok();
+    let floor_price = floor_price(deps.storage, &config, &club_name, ownership.as_ref())
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    let price = match listing {
+        Some(listing) if !listing_expired => listing.asking_price,
+        _ => floor_price,
+    };
+    Ok(ClubPriceResponse {
+        club_name,
+        purchasable,
+        price,
+        floor_price,
+    })
+}
+
+/// Hands the club over to the buyer for the given price, moving the current
+/// owner, if any, to the previous owner details. Returns the payouts of the
+/// price and the protocol fee taken from it.
+fn transfer_club_ownership(
+    deps: DepsMut,
+    env: &Env,
+    config: &Config,
+    club_name: String,
+    buyer: &Addr,
+    previous_owner: Option<ClubOwnershipDetails>,
+    price: Uint128,
+) -> StdResult<(Vec<(Addr, Uint128)>, Uint128)> {
+    if let Some(previous_owner) = &previous_owner {
+        OWNER_CLUBS.remove(
+            deps.storage,
+            (previous_owner.owner_address.clone(), club_name.clone()),
+        );
+    }
+    OWNER_CLUBS.save(deps.storage, (buyer.to_string(), club_name.clone()), &Empty {})?;
+    CLUB_OWNERSHIP_DETAILS.save(
+        deps.storage,
+        club_name.clone(),
+        &ClubOwnershipDetails {
+            club_name: club_name.clone(),
+            start_timestamp: env.block.time,
+            locking_period: config.club_locking_duration,
+            owner_address: buyer.to_string(),
+            price_paid: price,
+            reward_amount: config.club_buying_reward_amount,
+            owner_released: false,
+        },
+    )?;
+    append_ownership_record(
+        deps.storage,
+        &club_name,
+        buyer.to_string(),
+        env.block.time,
+        price,
+        config.club_buying_reward_amount,
+    )?;
+
+    // Split the price into the protocol fee and the seller's share.
+    // A club bought for the first time has no seller, so all of it
+    // goes to the treasury.
+    let protocol_fee = price.multiply_ratio(config.protocol_fee_percentage, 100u128);
+
+    let mut transfers = Vec::new();
+    match previous_owner {
+        Some(previous_owner) => {
+            let seller_addr = deps.api.addr_validate(&previous_owner.owner_address)?;
+            transfers.push((config.treasury_address.clone(), protocol_fee));
+            transfers.push((seller_addr, price - protocol_fee));
+
+            // Unclaimed rewards are added to whatever the seller still has
+            // from owning the club before
+            let key = (club_name.clone(), previous_owner.owner_address.clone());
+            PREVIOUS_OWNER_CLUBS.save(
+                deps.storage,
+                (previous_owner.owner_address.clone(), club_name.clone()),
+                &Empty {},
+            )?;
+            let unclaimed = CLUB_PREVIOUS_OWNER_DETAILS
+                .may_load(deps.storage, key.clone())?
+                .map(|details| details.reward_amount)
+                .unwrap_or_default();
+            CLUB_PREVIOUS_OWNER_DETAILS.save(
+                deps.storage,
+                key,
+                &ClubPreviousOwnerDetails {
+                    club_name,
+                    previous_owner_address: previous_owner.owner_address,
+                    reward_amount: unclaimed + previous_owner.reward_amount,
+                },
+            )?;
+        }
+        None => transfers.push((config.treasury_address.clone(), price)),
+    }
+    Ok((transfers, protocol_fee))
+}
+
+/// Closes the latest record in the ownership history of the club, if any,
+/// and appends one for the new owner
+fn append_ownership_record(
+    storage: &mut dyn Storage,
+    club_name: &str,
+    owner_address: String,
+    acquired_at: Timestamp,
+    price_paid: Uint128,
+    rewards_earned: Uint128,
+) -> StdResult<()> {
+    let length = CLUB_OWNERSHIP_HISTORY_LENGTH
+        .may_load(storage, club_name.to_string())?
+        .unwrap_or_default();
+    if length > 0 {
+        let key = (club_name.to_string(), U64Key::from(length - 1));
+        let mut latest = CLUB_OWNERSHIP_HISTORY.load(storage, key.clone())?;
+        latest.released_at = Some(acquired_at);
+        CLUB_OWNERSHIP_HISTORY.save(storage, key, &latest)?;
+    }
+    CLUB_OWNERSHIP_HISTORY.save(
+        storage,
+        (club_name.to_string(), U64Key::from(length)),
+        &ClubOwnershipRecord {
+            club_name: club_name.to_string(),
+            sequence: length,
+            owner_address,
+            acquired_at,
+            released_at: None,
+            price_paid,
+            rewards_earned,
+        },
+    )?;
+    CLUB_OWNERSHIP_HISTORY_LENGTH.save(storage, club_name.to_string(), &(length + 1))
+}
+
+/// Adds an owner reward to the rewards earned by the current owner of the club
+fn record_owner_reward(storage: &mut dyn Storage, club_name: &str, amount: Uint128) -> StdResult<()> {
+    let length = CLUB_OWNERSHIP_HISTORY_LENGTH
+        .may_load(storage, club_name.to_string())?
+        .unwrap_or_default();
+    if length == 0 {
+        return Ok(());
+    }
+    let key = (club_name.to_string(), U64Key::from(length - 1));
+    let mut latest = CLUB_OWNERSHIP_HISTORY.load(storage, key.clone())?;
+    latest.rewards_earned += amount;
+    CLUB_OWNERSHIP_HISTORY.save(storage, key, &latest)
+}
+
+fn start_auction(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    club_name: String,
+    reserve_price: Uint128,
+    min_bid_increment: Uint128,
+    duration: u64,
+) -> Result<Response, ContractError> {
+    let club_name = normalize_club_name(&club_name)?;
+    ensure_club_is_active(deps.storage, &club_name)?;
+    if CLUB_AUCTIONS.has(deps.storage, club_name.clone()) {
+        return Err(ContractError::ClubInAuction { club: club_name });
+    }
+    let listing = load_listing(deps.storage, &env.block, &club_name)?;
+    let owner = CLUB_OWNERSHIP_DETAILS
+        .may_load(deps.storage, club_name.clone())?
+        .ok_or_else(|| ContractError::ClubNotFound {
+            club: club_name.clone(),
+        })?;
+    if owner.owner_address != info.sender {
+        return Err(ContractError::NotClubOwner {
+            club: club_name,
+            address: info.sender.to_string(),
+        });
+    }
+    if !owner.owner_released {
+        return Err(ContractError::ClubNotReleased { club: club_name });
+    }
+    if listing.is_some() {
+        return Err(ContractError::ClubListed { club: club_name });
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let minimum = floor_price(deps.storage, &config, &club_name, Some(&owner))?;
+    if reserve_price < minimum {
+        return Err(ContractError::ReservePriceTooLow {
+            reserve_price,
+            minimum,
+        });
+    }
+    if duration == 0 {
+        return Err(ContractError::InvalidAuctionDuration {});
+    }
+    if min_bid_increment.is_zero() {
+        return Err(ContractError::InvalidBidIncrement {});
+    }
+
+    let auction = ClubAuction {
+        club_name: club_name.clone(),
+        seller: info.sender.clone(),
+        reserve_price,
+        min_bid_increment,
+        start_timestamp: env.block.time,
+        end_timestamp: env.block.time.plus_seconds(duration),
+        highest_bid: None,
+    };
+    CLUB_AUCTIONS.save(deps.storage, club_name.clone(), &auction)?;
+    return Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "start_auction"),
+            attr("club", club_name.clone()),
+            attr("address", info.sender.clone()),
+            attr("amount", reserve_price),
+        ])
+        .add_event(
+            new_event("auction_started", &env)
+                .add_attribute("club", club_name)
+                .add_attribute("address", info.sender)
+                .add_attribute("reserve_price", reserve_price)
+                .add_attribute("min_bid_increment", min_bid_increment)
+                .add_attribute("ends_at", auction.end_timestamp.seconds().to_string()),
+        ));
+}
+
+/// Escrows the bid and refunds the bid it beats. A bid in the last
+/// Config.auction_extension_duration seconds pushes the end of the auction
+/// out to that many seconds after the bid.
+fn place_bid(
     deps: DepsMut,
-    info: MessageInfo,
-    previous_owner: String,
+    env: Env,
+    bidder: String,
     club_name: String,
     amount: Uint128,
 ) -> Result<Response, ContractError> {
-    let previous_owner_addr = deps.api.addr_validate(&previous_owner)?;
-    //Check if withdrawer is same as invoker
-    if previous_owner_addr != info.sender {
+    let club_name = normalize_club_name(&club_name)?;
+    let bidder_addr = deps.api.addr_validate(&bidder)?;
+    ensure_club_is_active(deps.storage, &club_name)?;
+    let mut auction = CLUB_AUCTIONS
+        .may_load(deps.storage, club_name.clone())?
+        .ok_or_else(|| ContractError::NoAuction {
+            club: club_name.clone(),
+        })?;
+    if env.block.time >= auction.end_timestamp {
+        return Err(ContractError::AuctionEnded { club: club_name });
+    }
+    if bidder_addr == auction.seller {
         return Err(ContractError::Unauthorized {});
     }
 
-    let previous_ownership_details;
-    let previous_ownership_details_result = CLUB_PREVIOUS_OWNER_DETAILS.may_load(deps.storage, club_name.clone());
-    match previous_ownership_details_result {
-        Ok(od) => {
-            previous_ownership_details = od;
-        }
-        Err(e) => {
-            return Err(ContractError::Std(StdError::from(e)));
-        }
+    let minimum = match &auction.highest_bid {
+        Some(bid) => bid.amount + auction.min_bid_increment,
+        None => auction.reserve_price,
+    };
+    if amount < minimum {
+        return Err(ContractError::BidTooLow {
+            minimum,
+            bid: amount,
+        });
     }
 
-    if !(previous_ownership_details.is_none()) {
-        for previous_owner_detail in previous_ownership_details {
-            if previous_owner_detail.previous_owner_address == previous_owner.clone() {
-                if amount > previous_owner_detail.reward_amount {
-                    return Err(ContractError::Std(StdError::GenericErr {
-                        msg: String::from("Insufficient rewards"),
-                    }));
-                }
-
-                // TODO: Add amount to the owners wallet
-                // TODO: Assuming that above operation went good. Need to implement this
-
-
-                // Now save the previous ownership details
-                CLUB_PREVIOUS_OWNER_DETAILS.save(
-                    deps.storage,
-                    club_name.clone(),
-                    &ClubPreviousOwnerDetails {
-                        club_name: previous_owner_detail.club_name,
-                        previous_owner_address: previous_owner_detail.previous_owner_address,
-                        reward_amount: previous_owner_detail.reward_amount - amount,
-                    },
-                )?;
-            }
-        }
+    let config = CONFIG.load(deps.storage)?;
+    ensure_below_club_limit(deps.storage, &config, &bidder_addr)?;
+    let mut messages = Vec::new();
+    let mut event = new_event("bid_placed", &env)
+        .add_attribute("club", club_name.clone())
+        .add_attribute("address", bidder_addr.clone())
+        .add_attribute("amount", amount);
+    escrow_funds(deps.storage, amount)?;
+    if let Some(outbid) = auction.highest_bid.take() {
+        release_funds(deps.storage, outbid.amount)?;
+        messages.push(transfer_tokens_msg(&config, outbid.bidder.clone(), outbid.amount)?);
+        event = event
+            .add_attribute("refunded_bidder", outbid.bidder)
+            .add_attribute("refunded_amount", outbid.amount);
     }
-    return Ok(Response::default());
+    auction.highest_bid = Some(AuctionBid {
+        bidder: bidder_addr.clone(),
+        amount,
+        placed_at: env.block.time,
+    });
+    let extended_end = env.block.time.plus_seconds(config.auction_extension_duration);
+    if extended_end > auction.end_timestamp {
+        auction.end_timestamp = extended_end;
+    }
+    CLUB_AUCTIONS.save(deps.storage, club_name.clone(), &auction)?;
+
+    return Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![
+            attr("action", "place_bid"),
+            attr("club", club_name),
+            attr("address", bidder_addr),
+            attr("amount", amount),
+        ])
+        .add_event(event.add_attribute("ends_at", auction.end_timestamp.seconds().to_string())));
 }
 
-fn claim_owner_rewards (
+/// Anyone can settle an auction once it has ended. The highest bidder
+/// becomes the owner, without bids the club stays released with its seller.
+/// A highest bidder who has reached the club limit since bidding, through
+/// another auction for instance, gets the bid back and the club also stays
+/// with its seller.
+fn settle_auction(
     deps: DepsMut,
-    info: MessageInfo,
-    owner: String,
+    env: Env,
     club_name: String,
-    amount: Uint128,
 ) -> Result<Response, ContractError> {
-    let owner_addr = deps.api.addr_validate(&owner)?;
-    //Check if withdrawer is same as invoker
-    if owner_addr != info.sender {
-        return Err(ContractError::Unauthorized {});
+    let club_name = normalize_club_name(&club_name)?;
+    let auction = CLUB_AUCTIONS
+        .may_load(deps.storage, club_name.clone())?
+        .ok_or_else(|| ContractError::NoAuction {
+            club: club_name.clone(),
+        })?;
+    if env.block.time < auction.end_timestamp {
+        return Err(ContractError::AuctionNotEnded {
+            club: club_name,
+            ends_at: auction.end_timestamp,
+        });
     }
-
-    let ownership_details;
-    let ownership_details_result = CLUB_OWNERSHIP_DETAILS.may_load(deps.storage, club_name.clone());
-    match ownership_details_result {
-        Ok(od) => {
-            ownership_details = od;
+    CLUB_AUCTIONS.remove(deps.storage, club_name.clone());
+
+    let bid = match auction.highest_bid {
+        Some(bid) => bid,
+        None => {
+            return Ok(Response::new()
+                .add_attributes(vec![
+                    attr("action", "settle_auction"),
+                    attr("club", club_name.clone()),
+                ])
+                .add_event(new_event("auction_settled", &env).add_attribute("club", club_name)));
         }
-        Err(e) => {
-            return Err(ContractError::Std(StdError::from(e)));
+    };
+
+    release_funds(deps.storage, bid.amount)?;
+    let config = CONFIG.load(deps.storage)?;
+    if let Err(ContractError::TooManyClubs { .. }) =
+        ensure_below_club_limit(deps.storage, &config, &bid.bidder)
+    {
+        return Ok(Response::new()
+            .add_message(transfer_tokens_msg(&config, bid.bidder.clone(), bid.amount)?)
+            .add_attributes(vec![
+                attr("action", "settle_auction"),
+                attr("club", club_name.clone()),
+            ])
+            .add_event(
+                new_event("auction_settled", &env)
+                    .add_attribute("club", club_name)
+                    .add_attribute("refunded_bidder", bid.bidder)
+                    .add_attribute("refunded_amount", bid.amount),
+            ));
+    }
+    let previous_owner = CLUB_OWNERSHIP_DETAILS.may_load(deps.storage, club_name.clone())?;
+    let (transfers, protocol_fee) = transfer_club_ownership(
+        deps,
+        &env,
+        &config,
+        club_name.clone(),
+        &bid.bidder,
+        previous_owner,
+        bid.amount,
+    )?;
+    let mut messages = Vec::new();
+    for (recipient, transfer_amount) in transfers {
+        if transfer_amount > Uint128::zero() {
+            messages.push(transfer_tokens_msg(&config, recipient, transfer_amount)?);
         }
     }
+    return Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![
+            attr("action", "settle_auction"),
+            attr("club", club_name.clone()),
+            attr("address", bid.bidder.clone()),
+            attr("amount", bid.amount),
+        ])
+        .add_event(
+            new_event("auction_settled", &env)
+                .add_attribute("club", club_name)
+                .add_attribute("address", bid.bidder)
+                .add_attribute("seller", auction.seller)
+                .add_attribute("amount", bid.amount)
+                .add_attribute("protocol_fee", protocol_fee),
+        ));
+}
 
-    if !(ownership_details.is_none()) {
-        for owner_detail in ownership_details {
-            if owner_detail.owner_address == owner.clone() {
-                if amount > owner_detail.reward_amount {
-                    return Err(ContractError::Std(StdError::GenericErr {
-                        msg: String::from("Insufficient rewards"),
-                    }));
-                }
-
-                // TODO: Add amount to the owners wallet
-                // TODO: Assuming that above operation went good. Need to implement this
-
-                // Now save the ownership details
-                CLUB_OWNERSHIP_DETAILS.save(
-                    deps.storage,
-                    club_name.clone(),
-                    &ClubOwnershipDetails {
-                        club_name: owner_detail.club_name,
-                        start_timestamp: owner_detail.start_timestamp,
-                        locking_period: owner_detail.locking_period,
-                        owner_address: owner_detail.owner_address,
-                        price_paid: owner_detail.price_paid,
-                        reward_amount: owner_detail.reward_amount - amount,
-                        owner_released: owner_detail.owner_released,
-                    },
-                )?;
+/// Loads the listing of the club. An expired listing counts as no listing
+/// and is removed on the way, taking the club off sale until its owner
+/// lists or releases it again.
+fn load_listing(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    club_name: &str,
+) -> StdResult<Option<ClubListing>> {
+    match CLUB_LISTINGS.may_load(storage, club_name.to_string())? {
+        Some(listing) if listing.expiry.is_expired(block) => {
+            CLUB_LISTINGS.remove(storage, club_name.to_string());
+            if let Some(mut owner) = CLUB_OWNERSHIP_DETAILS.may_load(storage, club_name.to_string())? {
+                owner.owner_released = false;
+                CLUB_OWNERSHIP_DETAILS.save(storage, club_name.to_string(), &owner)?;
             }
+            Ok(None)
         }
+        listing => Ok(listing),
     }
+}
 
-    return Ok(Response::default());
+/// Loads the ownership of the club and checks that the sender owns it
+fn load_owned_club(
+    storage: &dyn Storage,
+    club_name: &str,
+    sender: &Addr,
+) -> Result<ClubOwnershipDetails, ContractError> {
+    let owner = CLUB_OWNERSHIP_DETAILS
+        .may_load(storage, club_name.to_string())?
+        .ok_or_else(|| ContractError::ClubNotFound {
+            club: club_name.to_string(),
+        })?;
+    if owner.owner_address != *sender {
+        return Err(ContractError::NotClubOwner {
+            club: club_name.to_string(),
+            address: sender.to_string(),
+        });
+    }
+    Ok(owner)
 }
 
-fn periodically_refund_stakeouts(
-    mut deps: DepsMut,
+/// A club can only change hands once its locking period is over
+fn ensure_club_unlocked(owner: &ClubOwnershipDetails, now: Timestamp) -> Result<(), ContractError> {
+    let until = owner.start_timestamp.plus_seconds(owner.locking_period);
+    if now < until {
+        return Err(ContractError::ClubLocked {
+            club: owner.club_name.clone(),
+            until,
+        });
+    }
+    Ok(())
+}
+
+/// Releases the club for sale at the asking price instead of Config.club_price.
+/// Listing an already listed club replaces the listing.
+fn list_club(
+    deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    club_name: String,
+    asking_price: Uint128,
+    expiry: Option<Expiration>,
 ) -> Result<Response, ContractError> {
-    //capture the current system time
-    let now = env.block.time;
-
-    let distribute_from = String::from(MAIN_WALLET);
-    let address = deps.api.addr_validate(distribute_from.clone().as_str())?;
-
-    //Check if the sender (one who is executing this contract) is main
-    if address != info.sender {
-        return Err(ContractError::Unauthorized {});
+    let club_name = normalize_club_name(&club_name)?;
+    ensure_club_is_active(deps.storage, &club_name)?;
+    if CLUB_AUCTIONS.has(deps.storage, club_name.clone()) {
+        return Err(ContractError::ClubInAuction { club: club_name });
     }
+    let mut owner = load_owned_club(deps.storage, &club_name, &info.sender)?;
+    ensure_club_unlocked(&owner, env.block.time)?;
 
-    // Fetch all bonding details 
-    let all_clubs: Vec<String> = CLUB_BONDING_DETAILS
-            .keys(deps.storage, None, None, Order::Ascending)
-            .map(|k| String::from_utf8(k).unwrap())
-            .collect();
-    for club_name in all_clubs {
-        let mut all_bonds = Vec::new();
-        let bonding_details = CLUB_BONDING_DETAILS.load(deps.storage, club_name.clone())?;
-        for mut bond in bonding_details {
-            let mut duration = bond.bonding_duration; 
-            let now_minus_duration_timestamp = now.minus_seconds(duration);
-            if now_minus_duration_timestamp < bond.bonding_start_timestamp {
-                all_bonds.push(bond);
-            } else {
-                // TODO : transfer to staker wallet
-            }
-        }
-        CLUB_BONDING_DETAILS.save(deps.storage, club_name, &all_bonds)?;
+    let config = CONFIG.load(deps.storage)?;
+    let minimum = floor_price(deps.storage, &config, &club_name, Some(&owner))?;
+    if asking_price < minimum {
+        return Err(ContractError::AskingPriceTooLow {
+            asking_price,
+            minimum,
+        });
     }
-    return Ok(Response::default());
+    let expiry = expiry.unwrap_or_default();
+    if expiry.is_expired(&env.block) {
+        return Err(ContractError::Expired {});
+    }
+
+    owner.owner_released = true;
+    CLUB_OWNERSHIP_DETAILS.save(deps.storage, club_name.clone(), &owner)?;
+    CLUB_LISTINGS.save(
+        deps.storage,
+        club_name.clone(),
+        &ClubListing {
+            club_name: club_name.clone(),
+            seller: info.sender.clone(),
+            asking_price,
+            expiry,
+            listed_at: env.block.time,
+        },
+    )?;
+    return Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "list_club"),
+            attr("club", club_name.clone()),
+            attr("address", info.sender.clone()),
+            attr("amount", asking_price),
+        ])
+        .add_event(
+            new_event("club_listed", &env)
+                .add_attribute("club", club_name)
+                .add_attribute("address", info.sender)
+                .add_attribute("amount", asking_price)
+                .add_attribute("expiry", expiry.to_string()),
+        ));
 }
 
-fn buy_a_club(
+/// Takes the club off sale again, also when it was released without a listing
+fn cancel_listing(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    buyer: String,
-    seller: String,
     club_name: String,
-    price: Uint128,
 ) -> Result<Response, ContractError> {
-    let buyer_addr = deps.api.addr_validate(&buyer)?;
-    //Check if buyer is same as invoker
-    if buyer_addr != info.sender {
-        return Err(ContractError::Unauthorized {});
+    let club_name = normalize_club_name(&club_name)?;
+    if CLUB_AUCTIONS.has(deps.storage, club_name.clone()) {
+        return Err(ContractError::ClubInAuction { club: club_name });
     }
-    let ownership_details;
-    let ownership_details_result = CLUB_OWNERSHIP_DETAILS.may_load(deps.storage, club_name.clone());
-    match ownership_details_result {
-        Ok(od) => {
-            ownership_details = od;
-        }
-        Err(e) => {
-            return Err(ContractError::Std(StdError::from(e)));
-        }
+    let mut owner = load_owned_club(deps.storage, &club_name, &info.sender)?;
+    if !owner.owner_released {
+        return Err(ContractError::ClubNotReleased { club: club_name });
     }
+    owner.owner_released = false;
+    CLUB_OWNERSHIP_DETAILS.save(deps.storage, club_name.clone(), &owner)?;
+    CLUB_LISTINGS.remove(deps.storage, club_name.clone());
+    return Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "cancel_listing"),
+            attr("club", club_name.clone()),
+            attr("address", info.sender.clone()),
+        ])
+        .add_event(
+            new_event("listing_cancelled", &env)
+                .add_attribute("club", club_name)
+                .add_attribute("address", info.sender),
+        ));
+}
 
-    let mut previous_owners_reward_amount = Uint128::from(0u128);
-    if !(ownership_details.is_none()) {
-        for owner in ownership_details {
-            if owner.owner_released == false {
-                return Err(ContractError::Std(StdError::GenericErr {
-                    msg: String::from("Owner has not released the club"),
-                }));
-            }
-            else if seller != "".to_string() && owner.owner_address != seller {
-                return Err(ContractError::Std(StdError::GenericErr {
-                    msg: String::from("Seller is not the owner for the club"),
-                }));
-            }
-            previous_owners_reward_amount = owner.reward_amount;
-        }
+/// Escrows an offer for an owned club. Another offer of the same bidder on
+/// the same club is added to the open one and replaces its expiry.
+fn make_offer(
+    deps: DepsMut,
+    env: Env,
+    bidder: String,
+    club_name: String,
+    amount: Uint128,
+    expiry: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    let club_name = normalize_club_name(&club_name)?;
+    let bidder_addr = deps.api.addr_validate(&bidder)?;
+    ensure_club_is_active(deps.storage, &club_name)?;
+    let owner = CLUB_OWNERSHIP_DETAILS
+        .may_load(deps.storage, club_name.clone())?
+        .ok_or_else(|| ContractError::ClubNotFound {
+            club: club_name.clone(),
+        })?;
+    if owner.owner_address == bidder_addr {
+        return Err(ContractError::Unauthorized {});
+    }
+    ensure_below_club_limit(deps.storage, &CONFIG.load(deps.storage)?, &bidder_addr)?;
+    let expiry = expiry.unwrap_or_default();
+    if expiry.is_expired(&env.block) {
+        return Err(ContractError::Expired {});
     }
 
-    // Deduct amount from the buyers wallet
-    // TODO: Assuming that above operation went good. Need to implement this
+    let key = (club_name.clone(), bidder_addr.to_string());
+    let offered = CLUB_OFFERS
+        .may_load(deps.storage, key.clone())?
+        .map(|offer| offer.amount)
+        .unwrap_or_default();
+    let offer = ClubOffer {
+        club_name: club_name.clone(),
+        bidder: bidder_addr.clone(),
+        amount: offered + amount,
+        expiry,
+        made_at: env.block.time,
+    };
+    CLUB_OFFERS.save(deps.storage, key, &offer)?;
+    escrow_funds(deps.storage, amount)?;
+    return Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "make_offer"),
+            attr("club", club_name.clone()),
+            attr("address", bidder_addr.clone()),
+            attr("amount", offer.amount),
+        ])
+        .add_event(
+            new_event("offer_made", &env)
+                .add_attribute("club", club_name)
+                .add_attribute("address", bidder_addr)
+                .add_attribute("amount", offer.amount)
+                .add_attribute("expiry", expiry.to_string()),
+        ));
+}
 
-    // Now save the ownership details
-    CLUB_OWNERSHIP_DETAILS.save(
-        deps.storage,
-        club_name.clone(),
-        &ClubOwnershipDetails {
-            club_name: club_name.clone(),
-            start_timestamp: env.block.time,
-            locking_period: CLUB_LOCKING_DURATION,
-            owner_address: buyer.clone(),
-            price_paid: price,
-            reward_amount: Uint128::from(CLUB_BUYING_REWARD_AMOUNT),
-            owner_released: false,
-        },
-    )?;
-    //If successfully bought save the funds in contract wallet
-    CONTRACT_WALLET.update(
-        deps.storage,
-        &buyer_addr,
-        |balance: Option<Uint128>| -> StdResult<_> { Ok(balance.unwrap_or_default() + price) },
-    )?;
+/// Refunds the open offer of the sender, expired or not
+fn withdraw_offer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    club_name: String,
+) -> Result<Response, ContractError> {
+    let club_name = normalize_club_name(&club_name)?;
+    let key = (club_name.clone(), info.sender.to_string());
+    let offer = CLUB_OFFERS
+        .may_load(deps.storage, key.clone())?
+        .ok_or_else(|| ContractError::NoOffer {
+            club: club_name.clone(),
+            bidder: info.sender.to_string(),
+        })?;
+    CLUB_OFFERS.remove(deps.storage, key);
+    release_funds(deps.storage, offer.amount)?;
 
-    if seller != "".to_string() {
-        // TODO : Update the seller wallet
-        let seller_addr = deps.api.addr_validate(&seller)?;
-        //If successfully bought save the funds in contract wallet
-        CONTRACT_WALLET.update(
-            deps.storage,
-            &seller_addr,
-            |balance: Option<Uint128>| -> StdResult<_> { Ok(Uint128::from(0u128)) },
-        )?;
+    let config = CONFIG.load(deps.storage)?;
+    return Ok(Response::new()
+        .add_message(transfer_tokens_msg(&config, offer.bidder.clone(), offer.amount)?)
+        .add_attributes(vec![
+            attr("action", "withdraw_offer"),
+            attr("club", club_name.clone()),
+            attr("address", offer.bidder.clone()),
+            attr("amount", offer.amount),
+        ])
+        .add_event(
+            new_event("offer_withdrawn", &env)
+                .add_attribute("club", club_name)
+                .add_attribute("address", offer.bidder)
+                .add_attribute("amount", offer.amount),
+        ));
+}
 
-        // Now save the previous ownership details
-        CLUB_PREVIOUS_OWNER_DETAILS.save(
-            deps.storage,
-            club_name.clone(),
-            &ClubPreviousOwnerDetails {
-                club_name: club_name.clone(),
-                previous_owner_address: seller.clone(),
-                reward_amount: previous_owners_reward_amount,
-            },
-        )?;
+/// Sells the club to the bidder for the escrowed offer. Goes through the
+/// same ownership transfer and fee split as a regular buy.
+fn accept_offer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    club_name: String,
+    bidder: String,
+) -> Result<Response, ContractError> {
+    let club_name = normalize_club_name(&club_name)?;
+    let bidder_addr = deps.api.addr_validate(&bidder)?;
+    ensure_club_is_active(deps.storage, &club_name)?;
+    if CLUB_AUCTIONS.has(deps.storage, club_name.clone()) {
+        return Err(ContractError::ClubInAuction { club: club_name });
     }
-    return Ok(Response::default());
+    let owner = load_owned_club(deps.storage, &club_name, &info.sender)?;
+    ensure_club_unlocked(&owner, env.block.time)?;
+
+    let key = (club_name.clone(), bidder_addr.to_string());
+    let offer = CLUB_OFFERS
+        .may_load(deps.storage, key.clone())?
+        .ok_or_else(|| ContractError::NoOffer {
+            club: club_name.clone(),
+            bidder: bidder_addr.to_string(),
+        })?;
+    if offer.expiry.is_expired(&env.block) {
+        return Err(ContractError::Expired {});
+    }
+    let config = CONFIG.load(deps.storage)?;
+    ensure_below_club_limit(deps.storage, &config, &offer.bidder)?;
+    let minimum = floor_price(deps.storage, &config, &club_name, Some(&owner))?;
+    if offer.amount < minimum {
+        return Err(ContractError::InsufficientFunds {
+            required: minimum,
+            sent: offer.amount,
+        });
+    }
+    CLUB_OFFERS.remove(deps.storage, key);
+    release_funds(deps.storage, offer.amount)?;
+    CLUB_LISTINGS.remove(deps.storage, club_name.clone());
+
+    let (transfers, protocol_fee) = transfer_club_ownership(
+        deps,
+        &env,
+        &config,
+        club_name.clone(),
+        &offer.bidder,
+        Some(owner),
+        offer.amount,
+    )?;
+    let mut messages = Vec::new();
+    for (recipient, transfer_amount) in transfers {
+        if transfer_amount > Uint128::zero() {
+            messages.push(transfer_tokens_msg(&config, recipient, transfer_amount)?);
+        }
+    }
+    return Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![
+            attr("action", "accept_offer"),
+            attr("club", club_name.clone()),
+            attr("address", offer.bidder.clone()),
+            attr("amount", offer.amount),
+        ])
+        .add_event(
+            new_event("offer_accepted", &env)
+                .add_attribute("club", club_name)
+                .add_attribute("address", offer.bidder)
+                .add_attribute("seller", info.sender)
+                .add_attribute("amount", offer.amount)
+                .add_attribute("protocol_fee", protocol_fee),
+        ));
+}
+
+/// Builds a cw20 Transfer of `amount` tokens from this contract to `recipient`
+fn transfer_tokens_msg(config: &Config, recipient: Addr, amount: Uint128) -> StdResult<WasmMsg> {
+    Ok(WasmMsg::Execute {
+        contract_addr: config.cw20_token_address.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount,
+        })?,
+        funds: vec![],
+    })
 }
 
 fn release_club(
@@ -385,11 +2485,14 @@ fn release_club(
     seller: String,
     club_name: String,
 ) -> Result<Response, ContractError> {
+    let club_name = normalize_club_name(&club_name)?;
     let seller_addr = deps.api.addr_validate(&seller)?;
     //Check if seller is same as invoker
     if seller_addr != info.sender {
         return Err(ContractError::Unauthorized {});
     }
+    // Clear an expired listing so it cannot take the club off sale again
+    load_listing(deps.storage, &env.block, &club_name)?;
     let ownership_details;
     let ownership_details_result = CLUB_OWNERSHIP_DETAILS.may_load(deps.storage, club_name.clone());
     match ownership_details_result {
@@ -403,24 +2506,24 @@ fn release_club(
 
     // check that the current ownership is with the seller
     if ownership_details.is_none() {
-        return Err(ContractError::Std(StdError::GenericErr {
-            msg: String::from("Releaser is not the owner for the club"),
-        }));
+        return Err(ContractError::ClubNotFound { club: club_name });
     }
     for owner in ownership_details {
         if owner.owner_address != seller_addr {
-            return Err(ContractError::Std(StdError::GenericErr {
-                msg: String::from("Releaser is not the owner for the club"),
-            }));
+            return Err(ContractError::NotClubOwner {
+                club: club_name,
+                address: seller_addr.to_string(),
+            });
         } else {
             //capture the current system time
             let now = env.block.time;
             let mut duration = owner.locking_period; 
             let now_minus_duration_timestamp = now.minus_seconds(duration);
             if now_minus_duration_timestamp < owner.start_timestamp {
-                return Err(ContractError::Std(StdError::GenericErr {
-                    msg: String::from("Locking period for the club is not over"),
-                }));
+                return Err(ContractError::ClubLocked {
+                    club: club_name,
+                    until: owner.start_timestamp.plus_seconds(duration),
+                });
             } else {
                 // Update the ownership details
                 CLUB_OWNERSHIP_DETAILS.save(
@@ -439,22 +2542,29 @@ fn release_club(
             }
         }
     }
-    return Ok(Response::default());
+    return Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "release_club"),
+            attr("club", club_name.clone()),
+            attr("address", seller_addr.clone()),
+        ])
+        .add_event(
+            new_event("club_released", &env)
+                .add_attribute("club", club_name)
+                .add_attribute("address", seller_addr),
+        ));
 }
 
 fn stake_on_a_club(
     deps: DepsMut,
     env: Env,
-    info: MessageInfo,
     staker: String,
     club_name: String,
     staked_amount: Uint128,
 ) -> Result<Response, ContractError> {
+    let club_name = normalize_club_name(&club_name)?;
     let staker_addr = deps.api.addr_validate(&staker)?;
-    //Check if buyer is same as invoker
-    if staker_addr != info.sender {
-        return Err(ContractError::Unauthorized {});
-    }
+    ensure_club_is_active(deps.storage, &club_name)?;
 
     //check if the club_name is available for staking
     let ownership_details;
@@ -471,12 +2581,11 @@ fn stake_on_a_club(
         // Now save the staking details
         save_staking_details(
             deps.storage,
-            env,
             staker.clone(),
             club_name.clone(),
             staked_amount,
             INCREASE_STAKE,
-        );
+        )?;
 
         //If successfully staked, save the funds in contract wallet
         CONTRACT_WALLET.update(
@@ -484,32 +2593,23 @@ fn stake_on_a_club(
             &staker_addr,
             |balance: Option<Uint128>| -> StdResult<_> { Ok(balance.unwrap_or_default() + staked_amount) },
         )?;
-
-        // TODO : uncomment here 
-        // // Deduct amount from the stakers wallet
-        // let config = CONFIG.load(deps.storage)?;
-        // let res = Response::new()
-        //     .add_message(WasmMsg::Execute {
-        //         contract_addr: config.cw20_token_address.to_string(),
-        //         funds: vec![],
-        //         msg: to_binary(&Cw20ExecuteMsg::Send {
-        //             contract: config.club_staking_address.to_string(),
-        //             amount: amount,
-        //             msg: to_binary(data: &T)
-        //         })?,
-        //     })
-        //     .add_attributes(vec![
-        //         attr("action", "stake"),
-        //         attr("club", club_name),
-        //         attr("address", info.sender),
-        //         attr("amount", amount),
-        //     ]);
+        escrow_funds(deps.storage, staked_amount)?;
     } else {
-        return Err(ContractError::Std(StdError::GenericErr {
-            msg: String::from("The club is not available for staking"),
-        }));
+        return Err(ContractError::ClubNotFound { club: club_name });
     }
-    return Ok(Response::default());
+    return Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "stake_on_a_club"),
+            attr("club", club_name.clone()),
+            attr("address", staker_addr.clone()),
+            attr("amount", staked_amount),
+        ])
+        .add_event(
+            new_event("stake_added", &env)
+                .add_attribute("club", club_name)
+                .add_attribute("address", staker_addr)
+                .add_attribute("amount", staked_amount),
+        ));
 }
 
 fn withdraw_stake_from_a_club(
@@ -521,6 +2621,7 @@ fn withdraw_stake_from_a_club(
     withdrawal_amount: Uint128,
     immediate_withdrawal: bool,
 ) -> Result<Response, ContractError> {
+    let club_name = normalize_club_name(&club_name)?;
     let staker_addr = deps.api.addr_validate(&staker)?;
     //Check if withdrawer is same as invoker
     if staker_addr != info.sender {
@@ -540,142 +2641,215 @@ fn withdraw_stake_from_a_club(
     }
 
     if ownership_details.is_some() {
-        // update funds in contract wallet
-        CONTRACT_WALLET.update(
-            deps.storage,
-            &staker_addr,
-            |balance: Option<Uint128>| -> StdResult<_> { Ok(balance.unwrap_or_default() - withdrawal_amount) },
-        )?;
-        
+        let staked_amount = club_staking_details()
+            .may_load(deps.storage, (club_name.clone(), staker.clone()))?
+            .map(|stake| stake.staked_amount)
+            .unwrap_or_default();
+        if withdrawal_amount > staked_amount {
+            return Err(ContractError::InsufficientStake {
+                requested: withdrawal_amount,
+                staked: staked_amount,
+            });
+        }
+
         if immediate_withdrawal == IMMEDIATE_WITHDRAWAL {
+            // update funds in contract wallet. Bonded amounts stay in the
+            // wallet until they are refunded
+            CONTRACT_WALLET.update(
+                deps.storage,
+                &staker_addr,
+                |balance: Option<Uint128>| -> StdResult<_> {
+                    Ok(balance.unwrap_or_default().checked_sub(withdrawal_amount)?)
+                },
+            )?;
+            release_funds(deps.storage, withdrawal_amount)?;
+
             // update the staking details
             save_staking_details(
                 deps.storage,
-                env,
                 staker.clone(),
                 club_name.clone(),
                 withdrawal_amount,
                 DECREASE_STAKE,
-            );
-
-            // TODO : Deduct 10% and burn it
+            )?;
 
-            // Remaining 90% transfer to staker wallet
-            // Also assuming that negative withdrawal means add
-            let refund_amount = withdrawal_amount 
-                .checked_mul(Uint128::from(90u128))
-                .unwrap_or_default()
-                .checked_div(Uint128::from(100u128))
-                .unwrap_or_default();
-            // TODO : uncomment here 
-            // // Add refund_amount to the stakers wallet
-            // let config = CONFIG.load(deps.storage)?;
-            // let res = Response::new()
-            //     .add_message(WasmMsg::Execute {
-            //         contract_addr: config.cw20_token_address.to_string(),
-            //         funds: vec![],
-            //         msg: to_binary(&Cw20ExecuteMsg::Send {
-            //             contract: config.club_staking_address.to_string(),
-            //             amount: 0 - refund_amount,
-            //             msg: to_binary(data: &T)
-            //         })?,
-            //     })
-            //     .add_attributes(vec![
-            //         attr("action", "stake"),
-            //         attr("club", club_name),
-            //         attr("address", info.sender),
-            //         attr("amount", refund_amount),
-            //     ]);
+            // Deduct the early withdrawal penalty and send the rest to the staker
+            let config = CONFIG.load(deps.storage)?;
+            let penalty_amount = withdrawal_amount.multiply_ratio(config.early_withdrawal_penalty_percentage, 100u128);
+            let refund_amount = withdrawal_amount - penalty_amount;
 
+            let mut messages = Vec::new();
+            if refund_amount > Uint128::zero() {
+                messages.push(transfer_tokens_msg(&config, staker_addr.clone(), refund_amount)?);
+            }
+            if penalty_amount > Uint128::zero() {
+                match config.penalty_destination {
+                    PenaltyDestination::Burn => {
+                        messages.push(WasmMsg::Execute {
+                            contract_addr: config.cw20_token_address.to_string(),
+                            msg: to_binary(&Cw20ExecuteMsg::Burn {
+                                amount: penalty_amount,
+                            })?,
+                            funds: vec![],
+                        });
+                    }
+                    PenaltyDestination::Treasury => {
+                        messages.push(transfer_tokens_msg(
+                            &config,
+                            config.treasury_address.clone(),
+                            penalty_amount,
+                        )?);
+                    }
+                    PenaltyDestination::RewardPool => {
+                        // The tokens stay in the contract and are handed out
+                        // with the next reward distribution
+                        let pool = PENALTY_POOL.may_load(deps.storage)?.unwrap_or_default();
+                        PENALTY_POOL.save(deps.storage, &(pool + penalty_amount))?;
+                    }
+                }
+            }
+            return Ok(Response::new()
+                .add_messages(messages)
+                .add_attributes(vec![
+                    attr("action", "immediate_withdrawal"),
+                    attr("club", club_name.clone()),
+                    attr("address", staker_addr.clone()),
+                    attr("amount", refund_amount),
+                    attr("penalty", penalty_amount),
+                ])
+                .add_event(
+                    new_event("stake_withdrawn", &env)
+                        .add_attribute("club", club_name)
+                        .add_attribute("address", staker_addr)
+                        .add_attribute("amount", withdrawal_amount)
+                        .add_attribute("penalty", penalty_amount),
+                ));
         } else {
             // update the staking details
             save_staking_details(
                 deps.storage,
-                env.clone(),
                 staker.clone(),
                 club_name.clone(),
                 withdrawal_amount,
                 DECREASE_STAKE,
-            );
+            )?;
 
             // Move the withdrawn stakes to bonding list. The actual refunding of bonded
             // amounts happens on a periodic basis in periodically_refund_stakeouts
+            let config = CONFIG.load(deps.storage)?;
             save_bonding_details(
                 deps.storage,
                 env.clone(),
                 staker.clone(),
                 club_name.clone(),
                 withdrawal_amount,
-                CLUB_BONDING_DURATION,
-            );
+                config.club_bonding_duration,
+            )?;
+            return Ok(Response::new()
+                .add_attributes(vec![
+                    attr("action", "bonded_withdrawal"),
+                    attr("club", club_name.clone()),
+                    attr("address", staker_addr.clone()),
+                    attr("amount", withdrawal_amount),
+                ])
+                .add_event(
+                    new_event("stake_withdrawn", &env)
+                        .add_attribute("club", club_name.clone())
+                        .add_attribute("address", staker_addr.clone())
+                        .add_attribute("amount", withdrawal_amount)
+                        .add_attribute("penalty", Uint128::zero()),
+                )
+                .add_event(
+                    new_event("bond_created", &env)
+                        .add_attribute("club", club_name)
+                        .add_attribute("address", staker_addr)
+                        .add_attribute("amount", withdrawal_amount)
+                        .add_attribute(
+                            "matures_at",
+                            env.block.time.plus_seconds(config.club_bonding_duration).seconds().to_string(),
+                        ),
+                ));
         }
     } else {
-        return Err(ContractError::Std(StdError::GenericErr {
-            msg: String::from("The club is not available for unstaking"),
-        }));
+        return Err(ContractError::ClubNotFound { club: club_name });
     }
-    return Ok(Response::default());
 }
 
 fn save_staking_details(
     storage: &mut dyn Storage,
-    env: Env,
     staker: String,
     club_name: String,
     amount: Uint128,
     increase_stake: bool,
 ) -> Result<Response, ContractError> {
-    // Get the exising stakes for this club
-    let mut stakes = Vec::new();
-    let all_stakes = CLUB_STAKING_DETAILS.may_load(storage, club_name.clone())?;
-    match all_stakes {
-        Some(some_stakes) => {
-            stakes = some_stakes;
-        }
-        None => {}
-    }
+    let key = (club_name.clone(), staker.clone());
+    let existing_stake = club_staking_details().may_load(storage, key.clone())?;
 
     // if already staked for this club, then increase or decrease the staked_amount in existing stake
-    let mut already_staked = false;
-    let existing_stakes = stakes.clone();
-    let mut updated_stakes = Vec::new();
-    for stake in existing_stakes {
-        let mut updated_stake = stake.clone();
-        if staker == stake.staker_address {
-            if increase_stake == INCREASE_STAKE {
-                updated_stake.staked_amount += amount;
-            } else {
-                if updated_stake.staked_amount >= amount {
-                    updated_stake.staked_amount -= amount;
-                } else {
-                    updated_stake.staked_amount = Uint128::from(0u128);
-                }
+    let mut stake = match existing_stake {
+        Some(stake) => stake,
+        None => {
+            if increase_stake != INCREASE_STAKE {
+                return Ok(Response::default());
+            }
+            ClubStakingDetails {
+                staker_address: staker,
+                staked_amount: Uint128::zero(),
+                club_name: club_name.clone(),
+                reward_amount: CONFIG.load(storage)?.club_staking_reward_amount,
+                ..Default::default()
             }
-            already_staked = true;
-        }
-        if updated_stake.staked_amount > Uint128::from(0u128) {
-            updated_stakes.push(updated_stake);
         }
+    };
+    settle_rewards(storage, &mut stake)?;
+    let previous_amount = stake.staked_amount;
+    if increase_stake == INCREASE_STAKE {
+        stake.staked_amount += amount;
+    } else if stake.staked_amount >= amount {
+        stake.staked_amount -= amount;
+    } else {
+        stake.staked_amount = Uint128::zero();
     }
-    if already_staked == true {
-        // save the modified stakes - with updation or removal of existing stake
-        CLUB_STAKING_DETAILS.save(storage, club_name, &updated_stakes)?;
-    } else if increase_stake == INCREASE_STAKE {
-        stakes.push(ClubStakingDetails {
-            // TODO duration and timestamp fields no longer needed - should be removed
-            staker_address: staker,
-            staking_start_timestamp: env.block.time,
-            staked_amount: amount,
-            staking_duration: CLUB_STAKING_DURATION,
-            club_name: club_name.clone(),
-            reward_amount: Uint128::from(CLUB_STAKING_REWARD_AMOUNT), // ensure that the first time reward amount is set to 0
-        });
-        CLUB_STAKING_DETAILS.save(storage, club_name, &stakes)?;
+
+    // save the modified stake - with updation or removal of existing stake.
+    // A stake is only dropped once its rewards have been claimed.
+    if stake.staked_amount > Uint128::zero() || stake.reward_amount > Uint128::zero() {
+        club_staking_details().save(storage, key, &stake)?;
+    } else {
+        club_staking_details().remove(storage, key)?;
+    }
+
+    let total_staked = TOTAL_STAKED.may_load(storage)?.unwrap_or_default();
+    TOTAL_STAKED.save(storage, &(total_staked + stake.staked_amount - previous_amount))?;
+
+    let previous_total = CLUB_STAKING_TOTALS.may_load(storage, club_name.clone())?.unwrap_or_default();
+    let total = previous_total + stake.staked_amount - previous_amount;
+    if previous_total > Uint128::zero() {
+        CLUB_LEADERBOARD.remove(storage, leaderboard_key(club_name.clone(), previous_total));
+    }
+    if total > Uint128::zero() {
+        CLUB_STAKING_TOTALS.save(storage, club_name.clone(), &total)?;
+        CLUB_LEADERBOARD.save(storage, leaderboard_key(club_name, total), &total)?;
+    } else {
+        CLUB_STAKING_TOTALS.remove(storage, club_name);
     }
 
     return Ok(Response::default());
 }
 
+/// Drops the club from the BONDER_CLUBS of the bonder once none of the
+/// bonds left in the club are theirs
+fn release_bonder_club(
+    storage: &mut dyn Storage,
+    bonder: String,
+    club_name: &str,
+    remaining_bonds: &[ClubBondingDetails],
+) {
+    if !remaining_bonds.iter().any(|bond| bond.bonder_address == bonder) {
+        BONDER_CLUBS.remove(storage, (bonder, club_name.to_string()));
+    }
+}
+
 fn save_bonding_details(
     storage: &mut dyn Storage,
     env: Env,
@@ -693,12 +2867,16 @@ fn save_bonding_details(
         }
         None => {}
     }
+    BONDER_CLUBS.save(storage, (bonder.clone(), club_name.clone()), &Empty {})?;
+    let bond_id = CLUB_BOND_SEQUENCE.may_load(storage, club_name.clone())?.unwrap_or_default();
+    CLUB_BOND_SEQUENCE.save(storage, club_name.clone(), &(bond_id + 1))?;
     bonds.push(ClubBondingDetails {
         bonder_address: bonder,
         bonding_start_timestamp: env.block.time,
         bonded_amount: bonded_amount,
         bonding_duration: duration,
         club_name: club_name.clone(),
+        bond_id,
     });
     CLUB_BONDING_DETAILS.save(storage, club_name, &bonds)?;
     return Ok(Response::default());
@@ -706,60 +2884,123 @@ fn save_bonding_details(
 
 fn set_reward_amount(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     amount: Uint128,
 ) -> Result<Response, ContractError> {
-    // Check if this is executed by main/transaction wallet
+    // Check if this is executed by the admin or a reward distributor
     let config = CONFIG.load(deps.storage)?;
-    if info.sender == config.cw20_token_address {
-        return Err(ContractError::Unauthorized {});
-    }
+    ensure_admin_or_role(deps.storage, &config, &info.sender, Role::RewardDistributor)?;
     REWARD.save(deps.storage, &amount)?;
-    return Ok(Response::default());
+    return Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "set_reward_amount"),
+            attr("address", info.sender.clone()),
+            attr("amount", amount),
+        ])
+        .add_event(
+            new_event("reward_amount_set", &env)
+                .add_attribute("address", info.sender)
+                .add_attribute("amount", amount),
+        ));
 }
 
 fn claim_rewards(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     staker: String,
     club_name: String,
     amount: Uint128,
 ) -> Result<Response, ContractError> {
+    let club_name = normalize_club_name(&club_name)?;
     let staker_addr = deps.api.addr_validate(&staker)?;
     //Check if withdrawer is same as invoker
     if staker_addr != info.sender {
         return Err(ContractError::Unauthorized {});
     }
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
 
-    // Get the exising stakes for this club
-    let mut stakes = Vec::new();
-    let all_stakes = CLUB_STAKING_DETAILS.may_load(deps.storage, club_name.clone())?;
-    match all_stakes {
-        Some(some_stakes) => {
-            stakes = some_stakes;
-        }
-        None => {}
+    // Get the exising stake for this club
+    let key = (club_name.clone(), staker.clone());
+    let mut stake = club_staking_details()
+        .may_load(deps.storage, key.clone())?
+        .ok_or(ContractError::InsufficientRewards {
+            requested: amount,
+            available: Uint128::zero(),
+        })?;
+    settle_rewards(deps.storage, &mut stake)?;
+    if amount > stake.reward_amount {
+        return Err(ContractError::InsufficientRewards {
+            requested: amount,
+            available: stake.reward_amount,
+        });
+    }
+    stake.reward_amount -= amount;
+    if stake.staked_amount > Uint128::zero() || stake.reward_amount > Uint128::zero() {
+        club_staking_details().save(deps.storage, key, &stake)?;
+    } else {
+        club_staking_details().remove(deps.storage, key)?;
     }
 
-    let existing_stakes = stakes.clone();
-    let mut updated_stakes = Vec::new();
-    for stake in existing_stakes {
-        let mut updated_stake = stake.clone();
-        if staker == stake.staker_address {
-            if amount <= updated_stake.reward_amount {
-                updated_stake.reward_amount -= amount;
-                // TODO: transfer to staker wallet
-            } else {
-                return Err(ContractError::Std(StdError::GenericErr {
-                    msg: String::from("Insufficient rewards"),
-                }));
-            }
-        }
-        updated_stakes.push(updated_stake);
+    return pay_out_reward(deps.as_ref(), &env, "claim_rewards", staker_addr, club_name, amount);
+}
+
+/// Adds tokens received on behalf of a user to ESCROWED_FUNDS
+fn escrow_funds(storage: &mut dyn Storage, amount: Uint128) -> StdResult<()> {
+    let escrowed = ESCROWED_FUNDS.may_load(storage)?.unwrap_or_default();
+    ESCROWED_FUNDS.save(storage, &(escrowed + amount))
+}
+
+/// Removes tokens paid back or passed on from ESCROWED_FUNDS
+fn release_funds(storage: &mut dyn Storage, amount: Uint128) -> StdResult<()> {
+    let escrowed = ESCROWED_FUNDS.may_load(storage)?.unwrap_or_default();
+    ESCROWED_FUNDS.save(storage, &escrowed.checked_sub(amount)?)
+}
+
+/// Transfers a claimed reward to the claimant, provided this contract
+/// holds enough tokens to cover it besides the escrowed funds of its users
+fn pay_out_reward(
+    deps: Deps,
+    env: &Env,
+    action: &str,
+    recipient: Addr,
+    club_name: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let contract_balance: BalanceResponse = deps.querier.query_wasm_smart(
+        config.cw20_token_address.clone(),
+        &Cw20QueryMsg::Balance {
+            address: env.contract.address.to_string(),
+        },
+    )?;
+    let escrowed = ESCROWED_FUNDS.may_load(deps.storage)?.unwrap_or_default();
+    let available = contract_balance.balance.saturating_sub(escrowed);
+    if available < amount {
+        return Err(ContractError::InsufficientContractBalance {
+            required: amount,
+            available,
+        });
     }
-    CLUB_STAKING_DETAILS.save(deps.storage, club_name, &updated_stakes)?;
 
-    return Ok(Response::default());
+    return Ok(Response::new()
+        .add_message(transfer_tokens_msg(&config, recipient.clone(), amount)?)
+        .add_attributes(vec![
+            attr("action", action),
+            attr("club", club_name.clone()),
+            attr("address", recipient.clone()),
+            attr("amount", amount),
+        ])
+        .add_event(
+            new_event("rewards_claimed", env)
+                .add_attribute("reward_type", action)
+                .add_attribute("club", club_name)
+                .add_attribute("address", recipient)
+                .add_attribute("amount", amount),
+        ));
 }
 
 fn calculate_and_distribute_rewards(
@@ -768,117 +3009,95 @@ fn calculate_and_distribute_rewards(
     info: MessageInfo,
 ) -> Result<Response, ContractError> {
 
-    // Check if this is executed by main/transaction wallet
+    // Check if this is executed by the admin or a reward distributor
     let config = CONFIG.load(deps.storage)?;
-    if info.sender == config.cw20_token_address {
-        return Err(ContractError::Unauthorized {});
-    }
-    let total_reward = REWARD.may_load(deps.storage)?.unwrap_or_default();
+    ensure_admin_or_role(deps.storage, &config, &info.sender, Role::RewardDistributor)?;
+    // The penalty pool is handed out once, on top of the standing reward
+    let penalty_pool = PENALTY_POOL.may_load(deps.storage)?.unwrap_or_default();
+    let total_reward = REWARD.may_load(deps.storage)?.unwrap_or_default() + penalty_pool;
+    let mut response = Response::new().add_attribute("action", "calculate_and_distribute_rewards");
     // No need to calculate if there is no reward amount
     if total_reward > Uint128::zero() {
         let mut reward_given_so_far = Uint128::zero();
-        // Get the club ranking as per staking
-        let top_rankers = get_clubs_ranking_by_stakes(deps.storage)?;
+        // Get the club on top of the staking leaderboard
+        let top_ranker = get_top_ranked_club(deps.storage)?;
         // No need to proceed if there are no stakers
-        if top_rankers.len() > 0 {
-            let winner_club = &top_rankers[0];
+        if let Some(winner_club) = top_ranker {
+            PENALTY_POOL.save(deps.storage, &Uint128::zero())?;
             let winner_club_name = winner_club.0.clone();
             let mut winner_club_details =
                 query_club_ownership_details(deps.storage, winner_club_name.clone())?;
-            println!("winner club owner address = {:?}", winner_club_details.owner_address);
-            //Increase owner funds by 1% of total reward
-            let winner_club_reward = total_reward
-                .checked_div(Uint128::from(100u128))
-                .unwrap_or_default();
+            //Increase owner funds by owner_reward_percentage (1%) of total reward
+            let winner_club_reward = total_reward.multiply_ratio(config.owner_reward_percentage, 100u128);
             winner_club_details.reward_amount += winner_club_reward;
             reward_given_so_far += winner_club_reward;
-            println!("winner club owner reward = {:?}", winner_club_reward);
             CLUB_OWNERSHIP_DETAILS.save(
                 deps.storage,
                 winner_club_details.club_name.clone(),
                 &winner_club_details,
             )?;
-            /* TODO: Discuss this 
-                This has been removed as reward should not be added to balance
+            record_owner_reward(deps.storage, &winner_club_name, winner_club_reward)?;
 
-            //Update the contract wallet with reward amount
-            CONTRACT_WALLET.update(
-                deps.storage,
-                &deps.api.addr_validate(&winner_club_details.owner_address)?,
-                |balance: Option<Uint128>| -> StdResult<_> {
-                    Ok(balance.unwrap_or_default() + winner_club_details.reward_amount)
-                },
-            )?;
-            */
-
-            //Get all stakes for this club
-            let mut stakes: Vec<ClubStakingDetails> = Vec::new();
-            let all_stakes_for_winner =
-                CLUB_STAKING_DETAILS.may_load(deps.storage, winner_club_name.clone())?;
-            match all_stakes_for_winner {
-                Some(some_stakes) => {
-                    stakes = some_stakes;
-                }
-                None => {}
-            }
-            let reward_for_all_winners = total_reward
-                .checked_mul(Uint128::from(19u128))
-                .unwrap_or_default()
-                .checked_div(Uint128::from(100u128))
-                .unwrap_or_default();
+            // Stakers are not visited here. Their share is added to the reward
+            // per staked token of the winner club and of all clubs, and each
+            // stake picks it up the next time it is settled.
+            let reward_for_all_winners = total_reward.multiply_ratio(config.winner_club_reward_percentage, 100u128);
             let total_staking_for_this_club = winner_club.1;
-            let mut updated_stakes = Vec::new();
-            for stake in stakes {
-                let reward_for_this_winner = reward_for_all_winners
-                    .checked_mul(stake.staked_amount)
-                    .unwrap_or_default()
-                    .checked_div(total_staking_for_this_club)
-                    .unwrap_or_default();
-                let mut updated_stake = stake.clone();
-                updated_stake.reward_amount += reward_for_this_winner;
-                reward_given_so_far += reward_for_this_winner;
-                updated_stakes.push(updated_stake);
-            }
-            CLUB_STAKING_DETAILS.save(deps.storage, winner_club_name.clone(), &updated_stakes)?;
-
-            // distribute the remaining 80% to all
-            let remaining_reward = total_reward
-                .checked_mul(Uint128::from(80u128))
-                .unwrap_or_default()
-                .checked_div(Uint128::from(100u128))
+            let club_index = CLUB_REWARD_INDEX
+                .may_load(deps.storage, winner_club_name.clone())?
                 .unwrap_or_default();
-            let mut total_staking = Uint128::zero();
-            let all_stakes = query_all_stakes(deps.storage)?;
-            for stake in all_stakes {
-                total_staking += stake.staked_amount;
-            }
-            let all_clubs: Vec<String> = CLUB_STAKING_DETAILS
-                .keys(deps.storage, None, None, Order::Ascending)
-                .map(|k| String::from_utf8(k).unwrap())
-                .collect();
-            for club_name in all_clubs {
-                let mut all_stakes = Vec::new();
-                let staking_details = CLUB_STAKING_DETAILS.load(deps.storage, club_name.clone())?;
-                for mut stake in staking_details {
-                    let reward_for_this_stake = (remaining_reward.checked_mul(stake.staked_amount))
-                        .unwrap_or_default()
-                        .checked_div(total_staking)
-                        .unwrap_or_default();
-                    stake.reward_amount += reward_for_this_stake;
-                    println!("reward for {:?} is {:?} ", stake.staker_address, stake.reward_amount);
-                    reward_given_so_far += reward_for_this_stake;
-                    all_stakes.push(stake);
-                }
-                CLUB_STAKING_DETAILS.save(deps.storage, club_name, &all_stakes)?;
-            }
-            println!("total reward given {:?} out of {:?}", reward_given_so_far, total_reward);
+            CLUB_REWARD_INDEX.save(
+                deps.storage,
+                winner_club_name.clone(),
+                &(club_index + Decimal::from_ratio(reward_for_all_winners, total_staking_for_this_club)),
+            )?;
+            reward_given_so_far += reward_for_all_winners;
+
+            // distribute the remaining all_stakers_reward_percentage (80%) to all
+            let remaining_reward = total_reward.multiply_ratio(config.all_stakers_reward_percentage, 100u128);
+            let total_staking = TOTAL_STAKED.may_load(deps.storage)?.unwrap_or_default();
+            let global_index = GLOBAL_REWARD_INDEX.may_load(deps.storage)?.unwrap_or_default();
+            GLOBAL_REWARD_INDEX.save(
+                deps.storage,
+                &(global_index + Decimal::from_ratio(remaining_reward, total_staking)),
+            )?;
+            reward_given_so_far += remaining_reward;
+            response = response
+                .add_attributes(vec![
+                    attr("club", winner_club_name.clone()),
+                    attr("amount", reward_given_so_far),
+                ])
+                .add_event(
+                    new_event("rewards_distributed", &env)
+                        .add_attribute("club", winner_club_name)
+                        .add_attribute("address", winner_club_details.owner_address)
+                        .add_attribute("amount", reward_given_so_far)
+                        .add_attribute("owner_reward", winner_club_reward)
+                        .add_attribute("winner_club_reward", reward_for_all_winners)
+                        .add_attribute("all_stakers_reward", remaining_reward),
+                );
         }
     }
-    return Ok(Response::default());
+    return Ok(response);
+}
+
+/// Adds the rewards a stake has accrued since it was last settled to its
+/// reward_amount and moves its snapshot of the reward indices to their
+/// current values. Must be called before staked_amount changes.
+pub(crate) fn settle_rewards(storage: &dyn Storage, stake: &mut ClubStakingDetails) -> StdResult<()> {
+    let global_index = GLOBAL_REWARD_INDEX.may_load(storage)?.unwrap_or_default();
+    let club_index = CLUB_REWARD_INDEX
+        .may_load(storage, stake.club_name.clone())?
+        .unwrap_or_default();
+    stake.reward_amount += stake.staked_amount * (global_index - stake.global_reward_index);
+    stake.reward_amount += stake.staked_amount * (club_index - stake.club_reward_index);
+    stake.global_reward_index = global_index;
+    stake.club_reward_index = club_index;
+    Ok(())
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Allowance { owner, spender } => {
             to_binary(&query_allowance(deps, owner, spender)?)
@@ -887,21 +3106,108 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             owner,
             start_after,
             limit,
-        } => to_binary(&query_allowance(deps, owner.clone(), owner.clone())?),
+        } => to_binary(&query_all_allowances(deps, owner, start_after, limit)?),
         QueryMsg::ClubStakingDetails { club_name } => {
-            to_binary(&query_club_staking_details(deps.storage, club_name)?)
+            to_binary(&StakesResponse {
+                stakes: query_club_staking_details(deps.storage, normalize_queried_club_name(&club_name)?)?,
+            })
         }
         QueryMsg::ClubBondingDetails { club_name } => {
-            to_binary(&query_club_bonding_details(deps.storage, club_name)?)
+            to_binary(&BondsResponse {
+                bonds: query_club_bonding_details(deps.storage, normalize_queried_club_name(&club_name)?)?,
+                last_key: None,
+            })
         }
         QueryMsg::ClubOwnershipDetails { club_name } => {
-            to_binary(&query_club_ownership_details(deps.storage, club_name)?)
+            to_binary(&query_club_ownership(deps, normalize_queried_club_name(&club_name)?)?)
+        }
+        QueryMsg::AllStakes {
+            club_name,
+            staker,
+            min_amount,
+            start_after,
+            limit,
+        } => {
+            let club_name = club_name.map(|club_name| normalize_queried_club_name(&club_name)).transpose()?;
+            let start_after = start_after
+                .map(|(club_name, staker)| Ok::<_, StdError>((normalize_queried_club_name(&club_name)?, staker)))
+                .transpose()?;
+            to_binary(&query_all_stakes(deps, club_name, staker, min_amount, start_after, limit)?)
+        }
+        QueryMsg::AllBonds {
+            club_name,
+            bonder,
+            min_amount,
+            start_after,
+            limit,
+        } => {
+            let club_name = club_name.map(|club_name| normalize_queried_club_name(&club_name)).transpose()?;
+            let start_after = start_after
+                .map(|(club_name, bond_id)| Ok::<_, StdError>((normalize_queried_club_name(&club_name)?, bond_id)))
+                .transpose()?;
+            to_binary(&query_all_bonds(deps, club_name, bonder, min_amount, start_after, limit)?)
+        }
+        QueryMsg::AllClubs {
+            owner,
+            start_after,
+            limit,
+        } => {
+            let start_after = start_after.map(|club_name| normalize_queried_club_name(&club_name)).transpose()?;
+            to_binary(&query_all_clubs(deps, owner, start_after, limit)?)
+        }
+        QueryMsg::ClubAuction { club_name } => {
+            to_binary(&query_club_auction(deps, normalize_queried_club_name(&club_name)?)?)
+        }
+        QueryMsg::OwnedClubs { owner } => to_binary(&query_owned_clubs(deps, owner)?),
+        QueryMsg::ClubOwnershipHistory {
+            club_name,
+            start_after,
+            limit,
+        } => {
+            let club_name = normalize_queried_club_name(&club_name)?;
+            to_binary(&query_club_ownership_history(deps, club_name, start_after, limit)?)
         }
-        QueryMsg::AllStakes {} => to_binary(&query_all_stakes(deps.storage)?),
-        QueryMsg::GetClubRankingByStakes {} => {
-            to_binary(&get_clubs_ranking_by_stakes(deps.storage)?)
+        QueryMsg::ClubPrice { club_name } => {
+            to_binary(&query_club_price(deps, env, normalize_queried_club_name(&club_name)?)?)
+        }
+        QueryMsg::ClubListings { start_after, limit } => {
+            let start_after = start_after.map(|club_name| normalize_queried_club_name(&club_name)).transpose()?;
+            to_binary(&query_club_listings(deps, &env.block, start_after, limit)?)
+        }
+        QueryMsg::ClubOffers {
+            club_name,
+            start_after,
+            limit,
+        } => {
+            let club_name = normalize_queried_club_name(&club_name)?;
+            to_binary(&query_club_offers(deps, &env.block, club_name, start_after, limit)?)
+        }
+        QueryMsg::Club { club_name } => {
+            to_binary(&query_club(deps, normalize_queried_club_name(&club_name)?)?)
+        }
+        QueryMsg::ClubRegistry {
+            status,
+            start_after,
+            limit,
+        } => {
+            let start_after = start_after.map(|club_name| normalize_queried_club_name(&club_name)).transpose()?;
+            to_binary(&query_club_registry(deps, status, start_after, limit)?)
+        }
+        QueryMsg::GetClubRankingByStakes { start_after, limit } => {
+            let start_after = start_after
+                .map(|(club_name, rank)| Ok::<_, StdError>((normalize_queried_club_name(&club_name)?, rank)))
+                .transpose()?;
+            to_binary(&query_club_ranking(deps, start_after, limit)?)
         }
         QueryMsg::RewardAmount {} => to_binary(&query_reward_amount(deps)?),
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::Roles {} => to_binary(&query_roles(deps.storage)?),
+        QueryMsg::StakerPortfolio { staker } => {
+            to_binary(&query_staker_portfolio(deps, staker)?)
+        }
+        QueryMsg::PendingAdmin {} => to_binary(&PendingAdminResponse {
+            pending_admin: PENDING_ADMIN.may_load(deps.storage)?,
+        }),
     }
 }
 
@@ -909,11 +3215,20 @@ pub fn query_club_staking_details(
     storage: &dyn Storage,
     club_name: String,
 ) -> StdResult<Vec<ClubStakingDetails>> {
-    let csd = CLUB_STAKING_DETAILS.may_load(storage, club_name)?;
-    match csd {
-        Some(csd) => return Ok(csd),
-        None => return Err(StdError::generic_err("No staking details found")),
-    };
+    let csd: StdResult<Vec<ClubStakingDetails>> = club_staking_details()
+        .prefix(club_name)
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| {
+            let mut stake = item?.1;
+            settle_rewards(storage, &mut stake)?;
+            Ok(stake)
+        })
+        .collect();
+    let csd = csd?;
+    if csd.is_empty() {
+        return Err(StdError::generic_err("No staking details found"));
+    }
+    return Ok(csd);
 }
 
 pub fn query_club_bonding_details(
@@ -927,61 +3242,183 @@ pub fn query_club_bonding_details(
     };
 }
 
-fn query_all_stakes(storage: &dyn Storage) -> StdResult<Vec<ClubStakingDetails>> {
-    let mut all_stakes = Vec::new();
-    let all_clubs: Vec<String> = CLUB_STAKING_DETAILS
-        .keys(storage, None, None, Order::Ascending)
-        .map(|k| String::from_utf8(k).unwrap())
-        .collect();
-    for club_name in all_clubs {
-        let staking_details = CLUB_STAKING_DETAILS.load(storage, club_name)?;
-        for stake in staking_details {
-            all_stakes.push(stake);
+fn query_staker_portfolio(deps: Deps, staker: String) -> StdResult<StakerPortfolioResponse> {
+    let staker_addr = deps.api.addr_validate(&staker)?;
+    let mut portfolio = StakerPortfolioResponse {
+        staker: staker_addr.to_string(),
+        stakes: vec![],
+        bonds: vec![],
+        owned_clubs: vec![],
+        previously_owned_clubs: vec![],
+        total_staked: Uint128::zero(),
+        total_pending_rewards: Uint128::zero(),
+        total_bonded: Uint128::zero(),
+        total_owner_rewards: Uint128::zero(),
+    };
+
+    let stakes = club_staking_details()
+        .idx
+        .staker
+        .prefix(staker_addr.as_bytes().to_vec())
+        .range(deps.storage, None, None, Order::Ascending);
+    for item in stakes {
+        let mut stake = item?.1;
+        settle_rewards(deps.storage, &mut stake)?;
+        portfolio.total_staked += stake.staked_amount;
+        portfolio.total_pending_rewards += stake.reward_amount;
+        portfolio.stakes.push(PortfolioStake {
+            club_name: stake.club_name,
+            staked_amount: stake.staked_amount,
+            pending_rewards: stake.reward_amount,
+        });
+    }
+
+    for club_name in staker_clubs(deps.storage, &BONDER_CLUBS, &staker_addr)? {
+        for bond in CLUB_BONDING_DETAILS.load(deps.storage, club_name)? {
+            if bond.bonder_address != staker_addr.as_str() {
+                continue;
+            }
+            portfolio.total_bonded += bond.bonded_amount;
+            portfolio.bonds.push(PortfolioBond {
+                matures_at: bond.bonding_start_timestamp.plus_seconds(bond.bonding_duration),
+                club_name: bond.club_name,
+                bonded_amount: bond.bonded_amount,
+            });
         }
     }
-    return Ok(all_stakes);
+
+    for club_name in staker_clubs(deps.storage, &OWNER_CLUBS, &staker_addr)? {
+        let owner = CLUB_OWNERSHIP_DETAILS.load(deps.storage, club_name)?;
+        portfolio.total_owner_rewards += owner.reward_amount;
+        portfolio.owned_clubs.push(PortfolioClub {
+            club_name: owner.club_name,
+            reward_amount: owner.reward_amount,
+        });
+    }
+    for club_name in staker_clubs(deps.storage, &PREVIOUS_OWNER_CLUBS, &staker_addr)? {
+        let previous_owner = CLUB_PREVIOUS_OWNER_DETAILS
+            .load(deps.storage, (club_name, staker_addr.to_string()))?;
+        portfolio.total_owner_rewards += previous_owner.reward_amount;
+        portfolio.previously_owned_clubs.push(PortfolioClub {
+            club_name: previous_owner.club_name,
+            reward_amount: previous_owner.reward_amount,
+        });
+    }
+    Ok(portfolio)
 }
 
-fn query_all_bonds(storage: &dyn Storage) -> StdResult<Vec<ClubBondingDetails>> {
-    let mut all_bonds = Vec::new();
-    let all_clubs: Vec<String> = CLUB_BONDING_DETAILS
+/// The clubs of an address in one of the (address, club name) reverse indexes
+fn staker_clubs(
+    storage: &dyn Storage,
+    index: &Map<(String, String), Empty>,
+    address: &Addr,
+) -> StdResult<Vec<String>> {
+    index
+        .prefix(address.to_string())
         .keys(storage, None, None, Order::Ascending)
-        .map(|k| String::from_utf8(k).unwrap())
-        .collect();
-    for club_name in all_clubs {
-        let bonding_details = CLUB_BONDING_DETAILS.load(storage, club_name)?;
-        for bond in bonding_details {
-            all_bonds.push(bond);
+        .map(|k| Ok(String::from_utf8(k)?))
+        .collect()
+}
+
+/// The club with the highest total stake, if anyone has staked at all
+fn get_top_ranked_club(storage: &dyn Storage) -> StdResult<Option<(String, Uint128)>> {
+    match CLUB_LEADERBOARD.range(storage, None, None, Order::Ascending).next() {
+        Some(item) => {
+            let (k, total) = item?;
+            Ok(Some((leaderboard_club_name(&k)?, total)))
         }
+        None => Ok(None),
     }
-    return Ok(all_bonds);
 }
 
-fn get_clubs_ranking_by_stakes(storage: &dyn Storage) -> StdResult<Vec<(String, Uint128)>> {
-    let mut all_stakes = Vec::new();
-    let all_clubs: Vec<String> = CLUB_STAKING_DETAILS
-        .keys(storage, None, None, Order::Ascending)
-        .map(|k| String::from_utf8(k).unwrap())
+fn query_roles(storage: &dyn Storage) -> StdResult<RolesResponse> {
+    let holders: StdResult<Vec<RoleHolder>> = ROLES
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (k, roles) = item?;
+            Ok(RoleHolder {
+                address: String::from_utf8(k)?,
+                roles,
+            })
+        })
         .collect();
-    for club_name in all_clubs {
-        let _tp = query_club_staking_details(storage, club_name.clone())?;
-        let mut staked_amount = Uint128::zero();
-        let mut club_name: Option<String> = None;
-        for stake in _tp {
-            staked_amount += stake.staked_amount;
-            if club_name.is_none() {
-                club_name = Some(stake.club_name.clone());
-            }
-        }
-        all_stakes.push((club_name.unwrap(), staked_amount));
-    }
-    all_stakes.sort_by(|a, b| b.1.cmp(&a.1));
-    return Ok(all_stakes);
+    Ok(RolesResponse { holders: holders? })
 }
 
-fn query_reward_amount(deps: Deps) -> StdResult<Uint128> {
+fn query_reward_amount(deps: Deps) -> StdResult<RewardAmountResponse> {
     let reward: Uint128 = REWARD.may_load(deps.storage)?.unwrap_or_default();
-    return Ok(reward);
+    let penalty_pool = PENALTY_POOL.may_load(deps.storage)?.unwrap_or_default();
+    return Ok(RewardAmountResponse {
+        amount: reward,
+        penalty_pool,
+    });
+}
+
+fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(ConfigResponse {
+        cw20_token_address: config.cw20_token_address.to_string(),
+        admin_address: config.admin_address.to_string(),
+        treasury_address: config.treasury_address.to_string(),
+        protocol_fee_percentage: config.protocol_fee_percentage,
+        early_withdrawal_penalty_percentage: config.early_withdrawal_penalty_percentage,
+        penalty_destination: config.penalty_destination,
+        club_price: config.club_price,
+        club_buying_reward_amount: config.club_buying_reward_amount,
+        club_staking_reward_amount: config.club_staking_reward_amount,
+        club_locking_duration: config.club_locking_duration,
+        club_bonding_duration: config.club_bonding_duration,
+        owner_reward_percentage: config.owner_reward_percentage,
+        winner_club_reward_percentage: config.winner_club_reward_percentage,
+        all_stakers_reward_percentage: config.all_stakers_reward_percentage,
+        club_creation_fee: config.club_creation_fee,
+        auction_extension_duration: config.auction_extension_duration,
+        pricing_model: config.pricing_model,
+        max_clubs_per_owner: config.max_clubs_per_owner,
+    })
+}
+
+fn query_club_ownership(deps: Deps, club_name: String) -> StdResult<ClubOwnershipDetailsResponse> {
+    let owner = query_club_ownership_details(deps.storage, club_name)?;
+    Ok(ClubOwnershipDetailsResponse {
+        club_name: owner.club_name,
+        owner_address: owner.owner_address,
+        start_timestamp: owner.start_timestamp,
+        locking_period: owner.locking_period,
+        price_paid: owner.price_paid,
+        reward_amount: owner.reward_amount,
+        owner_released: owner.owner_released,
+    })
+}
+
+fn query_club(deps: Deps, club_name: String) -> StdResult<ClubResponse> {
+    let club = CLUBS.load(deps.storage, club_name)?;
+    Ok(ClubResponse {
+        club_name: club.club_name,
+        display_name: club.display_name,
+        description: club.description,
+        logo: club.logo,
+        status: club.status,
+        creator: club.creator.to_string(),
+        created_at: club.created_at,
+    })
+}
+
+fn query_club_auction(deps: Deps, club_name: String) -> StdResult<ClubAuctionResponse> {
+    let auction = CLUB_AUCTIONS.load(deps.storage, club_name)?;
+    Ok(ClubAuctionResponse {
+        club_name: auction.club_name,
+        seller: auction.seller.to_string(),
+        reserve_price: auction.reserve_price,
+        min_bid_increment: auction.min_bid_increment,
+        start_timestamp: auction.start_timestamp,
+        end_timestamp: auction.end_timestamp,
+        highest_bid: auction.highest_bid.map(|bid| HighestBid {
+            bidder: bid.bidder.to_string(),
+            amount: bid.amount,
+            placed_at: bid.placed_at,
+        }),
+    })
 }
 
 fn query_club_ownership_details(
@@ -998,8 +3435,9 @@ fn query_club_ownership_details(
 fn query_club_previous_owner_details(
     storage: &dyn Storage,
     club_name: String,
+    previous_owner: String,
 ) -> StdResult<ClubPreviousOwnerDetails> {
-    let cod = CLUB_PREVIOUS_OWNER_DETAILS.may_load(storage, club_name)?;
+    let cod = CLUB_PREVIOUS_OWNER_DETAILS.may_load(storage, (club_name, previous_owner))?;
     match cod {
         Some(cod) => return Ok(cod),
         None => return Err(StdError::generic_err("No ownership details found")),
@@ -1008,27 +3446,113 @@ fn query_club_previous_owner_details(
 
 #[cfg(test)]
 mod tests {
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{coins, from_binary, Addr, CosmosMsg, StdError, SubMsg, WasmMsg};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+    use cosmwasm_std::{
+        coins, from_binary, from_slice, Addr, ContractResult, CosmosMsg, Empty, OwnedDeps, Querier,
+        QuerierResult, QueryRequest, StdError, SubMsg, SystemResult, WasmMsg, WasmQuery,
+    };
 
     use super::*;
-    use crate::msg::InstantiateMarketingInfo;
+    use crate::msg::{
+        ClubRank, ClubRankingResponse, ClubRegistryResponse, ClubsResponse, InstantiateMarketingInfo,
+        ClubOwnershipHistoryResponse, ListingsResponse, OffersResponse,
+    };
+    use crate::state::CONFIG_KEY;
 
     use cosmwasm_std::coin;
 
+    const CLUB_PRICE: u128 = 1000000000u128;
+
+    /// Every stake, unpaginated
+    fn query_all_stakes(storage: &dyn Storage) -> StdResult<Vec<ClubStakingDetails>> {
+        club_staking_details()
+            .range(storage, None, None, Order::Ascending)
+            .map(|item| {
+                let mut stake = item?.1;
+                settle_rewards(storage, &mut stake)?;
+                Ok(stake)
+            })
+            .collect()
+    }
+
+    /// Answers every cw20 Balance query with a fixed token balance
+    struct TokenQuerier {
+        base: MockQuerier,
+        token_balance: Uint128,
+    }
+
+    impl Querier for TokenQuerier {
+        fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+            let request: QueryRequest<Empty> = from_slice(bin_request).unwrap();
+            match request {
+                QueryRequest::Wasm(WasmQuery::Smart { .. }) => SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&BalanceResponse { balance: self.token_balance }).unwrap(),
+                )),
+                _ => self.base.raw_query(bin_request),
+            }
+        }
+    }
+
+    fn mock_dependencies_with_token_balance(
+        token_balance: Uint128,
+    ) -> OwnedDeps<MockStorage, MockApi, TokenQuerier> {
+        OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier: TokenQuerier {
+                base: MockQuerier::new(&[]),
+                token_balance,
+            },
+        }
+    }
+
+    fn instantiate_contract(mut deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            cw20_token_address: "cwtoken11111".to_string(),
+            admin_address: "admin11111".to_string(),
+            treasury_address: "treasury11111".to_string(),
+            protocol_fee_percentage: Uint128::from(5u128),
+            early_withdrawal_penalty_percentage: Uint128::from(10u128),
+            penalty_destination: PenaltyDestination::Burn,
+            club_price: Uint128::from(CLUB_PRICE),
+            club_buying_reward_amount: Uint128::from(100u128),
+            club_staking_reward_amount: Uint128::zero(),
+            club_locking_duration: 21 * 24 * 60 * 60,
+            club_bonding_duration: 7 * 24 * 60 * 60,
+            owner_reward_percentage: Uint128::from(1u128),
+            winner_club_reward_percentage: Uint128::from(19u128),
+            all_stakers_reward_percentage: Uint128::from(80u128),
+            club_creation_fee: Uint128::from(500u128),
+            auction_extension_duration: 10 * 60,
+            pricing_model: PricingModel::Fixed,
+            max_clubs_per_owner: 0,
+        };
+        let adminInfo = mock_info("admin11111", &[]);
+        instantiate(deps.branch(), mock_env(), adminInfo.clone(), instantiate_msg).unwrap();
+        for club_name in ["CLUB001", "CLUB002", "CLUB003", "CLUB004"] {
+            execute(deps.branch(), mock_env(), adminInfo.clone(), ExecuteMsg::CreateClub {
+                club_name: club_name.to_string(),
+                display_name: club_name.to_string(),
+                description: None,
+                logo: None,
+            }).unwrap();
+        }
+    }
+
     #[test]
     fn test_buying_of_club () {
         let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
 
         let owner1Info = mock_info("Owner001", &[coin(1000, "stake")]);
-        buy_a_club(deps.as_mut(), mock_env(), owner1Info.clone(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
-            Uint128::from(1000u128));
+        buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
+            Uint128::from(CLUB_PRICE));
 
         let queryRes = query_club_ownership_details(&mut deps.storage, "CLUB001".to_string());
         match queryRes {
             Ok(cod) => { 
                 assert_eq!(cod.owner_address, "Owner001".to_string());
-                assert_eq!(cod.price_paid, Uint128::from(1000u128));
+                assert_eq!(cod.price_paid, Uint128::from(CLUB_PRICE));
                 assert_eq!(cod.owner_released, false);
                 assert_eq!(cod.reward_amount, Uint128::from(100u128));
             }
@@ -1041,17 +3565,18 @@ mod tests {
 
     #[test]
     fn test_owner_claim_rewards () {
-        let mut deps = mock_dependencies(&[]);
+        let mut deps = mock_dependencies_with_token_balance(Uint128::from(1000000u128));
+        instantiate_contract(deps.as_mut());
 
         let owner1Info = mock_info("Owner001", &[coin(1000, "stake")]);
-        buy_a_club(deps.as_mut(), mock_env(), owner1Info.clone(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
-            Uint128::from(1000u128));
+        buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
+            Uint128::from(CLUB_PRICE));
 
         let queryRes = query_club_ownership_details(&mut deps.storage, "CLUB001".to_string());
         match queryRes {
             Ok(cod) => { 
                 assert_eq!(cod.owner_address, "Owner001".to_string());
-                assert_eq!(cod.price_paid, Uint128::from(1000u128));
+                assert_eq!(cod.price_paid, Uint128::from(CLUB_PRICE));
                 assert_eq!(cod.owner_released, false);
                 assert_eq!(cod.reward_amount, Uint128::from(100u128));
             }
@@ -1061,13 +3586,13 @@ mod tests {
             }
         }
 
-        claim_owner_rewards(deps.as_mut(), owner1Info.clone(), "Owner001".to_string(), "CLUB001".to_string(), Uint128::from(10u128));
+        claim_owner_rewards(deps.as_mut(), mock_env(), owner1Info.clone(), "Owner001".to_string(), "CLUB001".to_string(), Uint128::from(10u128));
 
         let queryResAfter = query_club_ownership_details(&mut deps.storage, "CLUB001".to_string());
         match queryResAfter {
             Ok(cod) => { 
                 assert_eq!(cod.owner_address, "Owner001".to_string());
-                assert_eq!(cod.price_paid, Uint128::from(1000u128));
+                assert_eq!(cod.price_paid, Uint128::from(CLUB_PRICE));
                 assert_eq!(cod.owner_released, false);
                 assert_eq!(cod.reward_amount, Uint128::from(90u128));
             }
@@ -1081,20 +3606,51 @@ mod tests {
     #[test]
     fn test_multiple_buying_of_club () {
         let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
 
         let owner1Info = mock_info("Owner001", &[coin(1000, "stake")]);
-        buy_a_club(deps.as_mut(), mock_env(), owner1Info.clone(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
-            Uint128::from(1000u128));
+        buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
+            Uint128::from(CLUB_PRICE));
 
         let owner2Info = mock_info("Owner002", &[coin(1000, "stake")]);
-        buy_a_club(deps.as_mut(), mock_env(), owner2Info.clone(), "Owner002".to_string(), "".to_string(), "CLUB001".to_string(),
-            Uint128::from(1000u128));
+        buy_a_club(deps.as_mut(), mock_env(), "Owner002".to_string(), "".to_string(), "CLUB001".to_string(),
+            Uint128::from(CLUB_PRICE));
+
+        let queryRes = query_club_ownership_details(&mut deps.storage, "CLUB001".to_string());
+        match queryRes {
+            Ok(cod) => { 
+                assert_eq!(cod.owner_address, "Owner001".to_string());
+                assert_eq!(cod.price_paid, Uint128::from(CLUB_PRICE));
+                assert_eq!(cod.owner_released, false);
+            }
+            Err(e) => {
+                println!("error parsing header: {:?}", e);
+                assert_eq!(1, 2);
+            }
+        }
+    }
+
+    #[test]
+    fn test_releasing_of_club_before_locking_period () {
+        let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
+
+        let owner1Info = mock_info("Owner001", &[coin(1000, "stake")]);
+        buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
+            Uint128::from(CLUB_PRICE));
+
+        let err = release_club(deps.as_mut(), mock_env(), owner1Info.clone(), "Owner001".to_string(), "CLUB001".to_string())
+            .unwrap_err();
+        assert_eq!(err, ContractError::ClubLocked {
+            club: "CLUB001".to_string(),
+            until: mock_env().block.time.plus_seconds(21 * 24 * 60 * 60),
+        });
 
         let queryRes = query_club_ownership_details(&mut deps.storage, "CLUB001".to_string());
         match queryRes {
             Ok(cod) => { 
                 assert_eq!(cod.owner_address, "Owner001".to_string());
-                assert_eq!(cod.price_paid, Uint128::from(1000u128));
+                assert_eq!(cod.price_paid, Uint128::from(CLUB_PRICE));
                 assert_eq!(cod.owner_released, false);
             }
             Err(e) => {
@@ -1105,79 +3661,713 @@ mod tests {
     }
 
     #[test]
-    fn test_releasing_of_club_before_locking_period () {
+    fn test_releasing_of_club_after_locking_period () {
+        let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
+
+        let owner1Info = mock_info("Owner001", &[coin(1000, "stake")]);
+        buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
+            Uint128::from(CLUB_PRICE));
+
+        release_club(deps.as_mut(), mock_env(), owner1Info.clone(), "Owner001".to_string(), "CLUB001".to_string());
+
+        let now = mock_env().block.time; // today
+
+        let queryRes = query_club_ownership_details(&mut deps.storage, "CLUB001".to_string());
+        match queryRes {
+            Ok(mut cod) => { 
+                assert_eq!(cod.owner_address, "Owner001".to_string());
+                assert_eq!(cod.price_paid, Uint128::from(CLUB_PRICE));
+                assert_eq!(cod.owner_released, false);
+                cod.start_timestamp = now.minus_seconds(22 * 24 * 60 * 60);
+                CLUB_OWNERSHIP_DETAILS.save(&mut deps.storage, "CLUB001".to_string(), &cod);
+            }
+            Err(e) => {
+                println!("error parsing header: {:?}", e);
+                assert_eq!(1, 2);
+            }
+        }
+
+        release_club(deps.as_mut(), mock_env(), owner1Info.clone(), "Owner001".to_string(), "CLUB001".to_string());
+
+        let queryResAfterReleasing = query_club_ownership_details(&mut deps.storage, "CLUB001".to_string());
+        match queryResAfterReleasing {
+            Ok(cod) => { 
+                assert_eq!(cod.owner_address, "Owner001".to_string());
+                assert_eq!(cod.price_paid, Uint128::from(CLUB_PRICE));
+                assert_eq!(cod.owner_released, true);
+            }
+            Err(e) => {
+                println!("error parsing header: {:?}", e);
+                assert_eq!(1, 2);
+            }
+        }
+    }
+
+    #[test]
+    fn test_auction_of_released_club() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
+
+        let owner1Info = mock_info("Owner001", &[]);
+        buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(), Uint128::from(CLUB_PRICE)).unwrap();
+        let mut cod = query_club_ownership_details(&mut deps.storage, "CLUB001".to_string()).unwrap();
+        cod.start_timestamp = mock_env().block.time.minus_seconds(22 * 24 * 60 * 60);
+        CLUB_OWNERSHIP_DETAILS.save(&mut deps.storage, "CLUB001".to_string(), &cod).unwrap();
+
+        let start_msg = |reserve_price: u128| ExecuteMsg::StartAuction {
+            club_name: "CLUB001".to_string(),
+            reserve_price: Uint128::from(reserve_price),
+            min_bid_increment: Uint128::from(100u128),
+            duration: 24 * 60 * 60,
+        };
+        let err = execute(deps.as_mut(), mock_env(), owner1Info.clone(), start_msg(CLUB_PRICE)).unwrap_err();
+        assert_eq!(err, ContractError::ClubNotReleased { club: "CLUB001".to_string() });
+
+        release_club(deps.as_mut(), mock_env(), owner1Info.clone(), "Owner001".to_string(), "CLUB001".to_string()).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), owner1Info.clone(), start_msg(CLUB_PRICE - 1)).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::ReservePriceTooLow {
+                reserve_price: Uint128::from(CLUB_PRICE - 1),
+                minimum: Uint128::from(CLUB_PRICE),
+            }
+        );
+        // An equal bid must not be able to displace the highest bidder
+        let err = execute(deps.as_mut(), mock_env(), owner1Info.clone(), ExecuteMsg::StartAuction {
+            club_name: "CLUB001".to_string(),
+            reserve_price: Uint128::from(CLUB_PRICE),
+            min_bid_increment: Uint128::zero(),
+            duration: 24 * 60 * 60,
+        }).unwrap_err();
+        assert_eq!(err, ContractError::InvalidBidIncrement {});
+        execute(deps.as_mut(), mock_env(), owner1Info.clone(), start_msg(CLUB_PRICE)).unwrap();
+
+        let err = buy_a_club(deps.as_mut(), mock_env(), "Owner002".to_string(), "Owner001".to_string(), "CLUB001".to_string(), Uint128::from(CLUB_PRICE)).unwrap_err();
+        assert_eq!(err, ContractError::ClubInAuction { club: "CLUB001".to_string() });
+
+        let tokenInfo = mock_info("cwtoken11111", &[]);
+        let bid_msg = |bidder: &str, amount: u128| ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: bidder.to_string(),
+            amount: Uint128::from(amount),
+            msg: to_binary(&ReceiveMsg::PlaceBid { club_name: "CLUB001".to_string() }).unwrap(),
+        });
+        let err = execute(deps.as_mut(), mock_env(), tokenInfo.clone(), bid_msg("Bidder001", CLUB_PRICE - 1)).unwrap_err();
+        assert_eq!(err, ContractError::BidTooLow { minimum: Uint128::from(CLUB_PRICE), bid: Uint128::from(CLUB_PRICE - 1) });
+        let res = execute(deps.as_mut(), mock_env(), tokenInfo.clone(), bid_msg("Bidder001", CLUB_PRICE)).unwrap();
+        assert_eq!(res.messages.len(), 0);
+
+        let err = execute(deps.as_mut(), mock_env(), tokenInfo.clone(), bid_msg("Bidder002", CLUB_PRICE + 50)).unwrap_err();
+        assert_eq!(err, ContractError::BidTooLow { minimum: Uint128::from(CLUB_PRICE + 100), bid: Uint128::from(CLUB_PRICE + 50) });
+
+        // Outbidding refunds the previous bid, and a bid near the end extends the auction
+        let mut lateEnv = mock_env();
+        lateEnv.block.time = lateEnv.block.time.plus_seconds(24 * 60 * 60 - 60);
+        let res = execute(deps.as_mut(), lateEnv.clone(), tokenInfo.clone(), bid_msg("Bidder002", CLUB_PRICE + 100)).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: "cwtoken11111".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "Bidder001".to_string(),
+                    amount: Uint128::from(CLUB_PRICE),
+                })
+                .unwrap(),
+                funds: vec![],
+            })]
+        );
+        let auction: ClubAuctionResponse = from_binary(
+            &query(deps.as_ref(), mock_env(), QueryMsg::ClubAuction { club_name: "CLUB001".to_string() }).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(auction.end_timestamp, lateEnv.block.time.plus_seconds(10 * 60));
+        assert_eq!(auction.highest_bid.unwrap().bidder, "Bidder002".to_string());
+
+        let mut settleEnv = mock_env();
+        settleEnv.block.time = lateEnv.block.time.plus_seconds(60);
+        let err = execute(deps.as_mut(), settleEnv.clone(), mock_info("anyone", &[]), ExecuteMsg::SettleAuction { club_name: "CLUB001".to_string() }).unwrap_err();
+        assert_eq!(err, ContractError::AuctionNotEnded { club: "CLUB001".to_string(), ends_at: auction.end_timestamp });
+
+        settleEnv.block.time = auction.end_timestamp;
+        let res = execute(deps.as_mut(), settleEnv, mock_info("anyone", &[]), ExecuteMsg::SettleAuction { club_name: "CLUB001".to_string() }).unwrap();
+        // protocol fee to the treasury and the rest to the seller
+        assert_eq!(res.messages.len(), 2);
+
+        let cod = query_club_ownership_details(&mut deps.storage, "CLUB001".to_string()).unwrap();
+        assert_eq!(cod.owner_address, "Bidder002".to_string());
+        assert_eq!(cod.price_paid, Uint128::from(CLUB_PRICE + 100));
+        assert_eq!(cod.owner_released, false);
+        let previous = CLUB_PREVIOUS_OWNER_DETAILS.load(&deps.storage, ("CLUB001".to_string(), "Owner001".to_string())).unwrap();
+        assert_eq!(previous.previous_owner_address, "Owner001".to_string());
+        assert!(!CLUB_AUCTIONS.has(&deps.storage, "CLUB001".to_string()));
+    }
+
+    #[test]
+    fn test_max_clubs_per_owner() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
+        let mut config = CONFIG.load(&deps.storage).unwrap();
+        config.max_clubs_per_owner = 2;
+        CONFIG.save(&mut deps.storage, &config).unwrap();
+
+        let buy = |deps: DepsMut, buyer: &str, seller: &str, club_name: &str| {
+            buy_a_club(deps, mock_env(), buyer.to_string(), seller.to_string(), club_name.to_string(), Uint128::from(CLUB_PRICE))
+        };
+        buy(deps.as_mut(), "Owner001", "", "CLUB001").unwrap();
+        buy(deps.as_mut(), "Owner001", "", "CLUB002").unwrap();
+        let err = buy(deps.as_mut(), "Owner001", "", "CLUB003").unwrap_err();
+        assert_eq!(err, ContractError::TooManyClubs { owner: "Owner001".to_string(), max: 2 });
+
+        let owned_clubs = |deps: Deps, owner: &str| -> Vec<String> {
+            let res: OwnedClubsResponse =
+                from_binary(&query(deps, mock_env(), QueryMsg::OwnedClubs { owner: owner.to_string() }).unwrap()).unwrap();
+            res.clubs.into_iter().map(|club| club.club_name).collect()
+        };
+        assert_eq!(owned_clubs(deps.as_ref(), "Owner001"), vec!["CLUB001", "CLUB002"]);
+
+        // Selling a club frees up room for another one
+        let mut cod = query_club_ownership_details(&deps.storage, "CLUB001".to_string()).unwrap();
+        cod.start_timestamp = mock_env().block.time.minus_seconds(22 * 24 * 60 * 60);
+        CLUB_OWNERSHIP_DETAILS.save(&mut deps.storage, "CLUB001".to_string(), &cod).unwrap();
+        release_club(deps.as_mut(), mock_env(), mock_info("Owner001", &[]), "Owner001".to_string(), "CLUB001".to_string()).unwrap();
+        buy(deps.as_mut(), "Owner002", "Owner001", "CLUB001").unwrap();
+        buy(deps.as_mut(), "Owner001", "", "CLUB003").unwrap();
+        assert_eq!(owned_clubs(deps.as_ref(), "Owner001"), vec!["CLUB002", "CLUB003"]);
+        assert_eq!(owned_clubs(deps.as_ref(), "Owner002"), vec!["CLUB001"]);
+
+        // The index is rebuilt from the ownership details on migration
+        OWNER_CLUBS.remove(&mut deps.storage, ("Owner002".to_string(), "CLUB001".to_string()));
+        set_contract_version(&mut deps.storage, CONTRACT_NAME, "0.15.0").unwrap();
+        migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap();
+        assert_eq!(owned_clubs(deps.as_ref(), "Owner002"), vec!["CLUB001"]);
+    }
+
+    #[test]
+    fn test_migrate_rebuilds_contract_wallets_from_stakes_and_bonds() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
+        buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
+            Uint128::from(CLUB_PRICE)).unwrap();
+        stake_on_a_club(deps.as_mut(), mock_env(), "Staker001".to_string(), "CLUB001".to_string(),
+            Uint128::from(50u128)).unwrap();
+        withdraw_stake_from_a_club(deps.as_mut(), mock_env(), mock_info("Staker001", &[]), "Staker001".to_string(),
+            "CLUB001".to_string(), Uint128::from(7u128), NO_IMMEDIATE_WITHDRAWAL).unwrap();
+        make_offer(deps.as_mut(), mock_env(), "Bidder001".to_string(), "CLUB001".to_string(),
+            Uint128::from(CLUB_PRICE), None).unwrap();
+
+        // Wallets as left behind by 0.9.1, which credited the price paid for
+        // a club to the buyer and zeroed the seller
+        CONTRACT_WALLET.save(&mut deps.storage, &Addr::unchecked("Owner001"), &Uint128::from(CLUB_PRICE)).unwrap();
+        CONTRACT_WALLET.save(&mut deps.storage, &Addr::unchecked("Staker001"), &Uint128::zero()).unwrap();
+        ESCROWED_FUNDS.remove(&mut deps.storage);
+        set_contract_version(&mut deps.storage, CONTRACT_NAME, "0.16.0").unwrap();
+
+        migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap();
+        assert!(!CONTRACT_WALLET.has(&deps.storage, &Addr::unchecked("Owner001")));
+        assert_eq!(CONTRACT_WALLET.load(&deps.storage, &Addr::unchecked("Staker001")).unwrap(), Uint128::from(50u128));
+        assert_eq!(ESCROWED_FUNDS.load(&deps.storage).unwrap(), Uint128::from(50u128 + CLUB_PRICE));
+
+        // The staker can take the stake out again
+        withdraw_stake_from_a_club(deps.as_mut(), mock_env(), mock_info("Staker001", &[]), "Staker001".to_string(),
+            "CLUB001".to_string(), Uint128::from(43u128), IMMEDIATE_WITHDRAWAL).unwrap();
+    }
+
+    #[test]
+    fn test_club_limit_is_checked_again_when_settling_auctions() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
+        let mut config = CONFIG.load(&deps.storage).unwrap();
+        config.max_clubs_per_owner = 1;
+        CONFIG.save(&mut deps.storage, &config).unwrap();
+
+        // Two clubs in auction at the same time, both led by Bidder001
+        for (seller, club_name) in [("Owner001", "CLUB001"), ("Owner002", "CLUB002")] {
+            buy_a_club(deps.as_mut(), mock_env(), seller.to_string(), "".to_string(), club_name.to_string(), Uint128::from(CLUB_PRICE)).unwrap();
+            let mut cod = query_club_ownership_details(&deps.storage, club_name.to_string()).unwrap();
+            cod.start_timestamp = mock_env().block.time.minus_seconds(22 * 24 * 60 * 60);
+            CLUB_OWNERSHIP_DETAILS.save(&mut deps.storage, club_name.to_string(), &cod).unwrap();
+            release_club(deps.as_mut(), mock_env(), mock_info(seller, &[]), seller.to_string(), club_name.to_string()).unwrap();
+            execute(deps.as_mut(), mock_env(), mock_info(seller, &[]), ExecuteMsg::StartAuction {
+                club_name: club_name.to_string(),
+                reserve_price: Uint128::from(CLUB_PRICE),
+                min_bid_increment: Uint128::from(100u128),
+                duration: 24 * 60 * 60,
+            }).unwrap();
+            place_bid(deps.as_mut(), mock_env(), "Bidder001".to_string(), club_name.to_string(), Uint128::from(CLUB_PRICE)).unwrap();
+        }
+
+        let mut endedEnv = mock_env();
+        endedEnv.block.time = endedEnv.block.time.plus_seconds(24 * 60 * 60);
+        settle_auction(deps.as_mut(), endedEnv.clone(), "CLUB001".to_string()).unwrap();
+        assert_eq!(query_club_ownership_details(&deps.storage, "CLUB001".to_string()).unwrap().owner_address, "Bidder001".to_string());
+
+        // The second win would take Bidder001 over the limit
+        let res = settle_auction(deps.as_mut(), endedEnv, "CLUB002".to_string()).unwrap();
+        assert_eq!(res.messages, vec![SubMsg::new(WasmMsg::Execute {
+            contract_addr: "cwtoken11111".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "Bidder001".to_string(),
+                amount: Uint128::from(CLUB_PRICE),
+            }).unwrap(),
+            funds: vec![],
+        })]);
+        let cod = query_club_ownership_details(&deps.storage, "CLUB002".to_string()).unwrap();
+        assert_eq!(cod.owner_address, "Owner002".to_string());
+        assert!(cod.owner_released);
+        assert!(!CLUB_AUCTIONS.has(&deps.storage, "CLUB002".to_string()));
+        assert_eq!(ESCROWED_FUNDS.load(&deps.storage).unwrap(), Uint128::zero());
+        let err = execute(deps.as_mut(), mock_env(), mock_info("Owner002", &[]), ExecuteMsg::SettleAuction { club_name: "CLUB002".to_string() }).unwrap_err();
+        assert_eq!(err, ContractError::NoAuction { club: "CLUB002".to_string() });
+    }
+
+    #[test]
+    fn test_ownership_history_and_previous_owner_balances() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
+
+        let age_and_release = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, owner: &str| {
+            let mut cod = query_club_ownership_details(&deps.storage, "CLUB001".to_string()).unwrap();
+            cod.start_timestamp = mock_env().block.time.minus_seconds(22 * 24 * 60 * 60);
+            CLUB_OWNERSHIP_DETAILS.save(&mut deps.storage, "CLUB001".to_string(), &cod).unwrap();
+            release_club(deps.as_mut(), mock_env(), mock_info(owner, &[]), owner.to_string(), "CLUB001".to_string()).unwrap();
+        };
+        buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(), Uint128::from(CLUB_PRICE)).unwrap();
+        age_and_release(&mut deps, "Owner001");
+        let mut laterEnv = mock_env();
+        laterEnv.block.time = laterEnv.block.time.plus_seconds(60);
+        buy_a_club(deps.as_mut(), laterEnv.clone(), "Owner002".to_string(), "Owner001".to_string(), "CLUB001".to_string(), Uint128::from(CLUB_PRICE)).unwrap();
+        age_and_release(&mut deps, "Owner002");
+        buy_a_club(deps.as_mut(), mock_env(), "Owner003".to_string(), "Owner002".to_string(), "CLUB001".to_string(), Uint128::from(CLUB_PRICE)).unwrap();
+
+        // Selling on does not wipe the balance of an earlier owner
+        let balance = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>, owner: &str| {
+            query_club_previous_owner_details(&deps.storage, "CLUB001".to_string(), owner.to_string()).unwrap().reward_amount
+        };
+        assert_eq!(balance(&deps, "Owner001"), Uint128::from(100u128));
+        assert_eq!(balance(&deps, "Owner002"), Uint128::from(100u128));
+
+        let history: ClubOwnershipHistoryResponse = from_binary(
+            &query(deps.as_ref(), mock_env(), QueryMsg::ClubOwnershipHistory { club_name: "CLUB001".to_string(), start_after: None, limit: None }).unwrap(),
+        )
+        .unwrap();
+        let owners: Vec<String> = history.history.iter().map(|record| record.owner_address.clone()).collect();
+        assert_eq!(owners, vec!["Owner001", "Owner002", "Owner003"]);
+        assert_eq!(history.history[0].released_at, Some(laterEnv.block.time));
+        assert_eq!(history.history[1].acquired_at, laterEnv.block.time);
+        assert_eq!(history.history[1].rewards_earned, Uint128::from(100u128));
+        assert_eq!(history.history[2].released_at, None);
+
+        let page: ClubOwnershipHistoryResponse = from_binary(
+            &query(deps.as_ref(), mock_env(), QueryMsg::ClubOwnershipHistory { club_name: "CLUB001".to_string(), start_after: Some(0), limit: Some(1) }).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(page.history, vec![history.history[1].clone()]);
+    }
+
+    #[test]
+    fn test_migrate_keeps_previous_owner_balances_per_owner() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
+        buy_a_club(deps.as_mut(), mock_env(), "Owner002".to_string(), "".to_string(), "CLUB001".to_string(), Uint128::from(CLUB_PRICE)).unwrap();
+        CLUB_OWNERSHIP_HISTORY.remove(&mut deps.storage, ("CLUB001".to_string(), U64Key::from(0)));
+        CLUB_OWNERSHIP_HISTORY_LENGTH.remove(&mut deps.storage, "CLUB001".to_string());
+        CLUB_PREVIOUS_OWNER_DETAILS_V0_14.save(&mut deps.storage, "CLUB001".to_string(), &ClubPreviousOwnerDetails {
+            club_name: "CLUB001".to_string(),
+            previous_owner_address: "Owner001".to_string(),
+            reward_amount: Uint128::from(42u128),
+        }).unwrap();
+        set_contract_version(&mut deps.storage, CONTRACT_NAME, "0.14.0").unwrap();
+
+        migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap();
+
+        assert_eq!(CLUB_PREVIOUS_OWNER_DETAILS_V0_14.may_load(&deps.storage, "CLUB001".to_string()).unwrap(), None);
+        let previous = query_club_previous_owner_details(&deps.storage, "CLUB001".to_string(), "Owner001".to_string()).unwrap();
+        assert_eq!(previous.reward_amount, Uint128::from(42u128));
+        let record = CLUB_OWNERSHIP_HISTORY.load(&deps.storage, ("CLUB001".to_string(), U64Key::from(0))).unwrap();
+        assert_eq!(record.owner_address, "Owner002".to_string());
+        assert_eq!(record.price_paid, Uint128::from(CLUB_PRICE));
+        assert_eq!(record.released_at, None);
+    }
+
+    #[test]
+    fn test_migrate_normalizes_club_names() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
+        let raw = "club  005".to_string();
+        let normalized = "CLUB 005".to_string();
+
+        // State written under the raw name, as before names were normalized
+        CLUBS.save(&mut deps.storage, raw.clone(), &ClubInfo {
+            club_name: raw.clone(),
+            display_name: raw.clone(),
+            description: None,
+            logo: None,
+            status: ClubStatus::Active,
+            creator: Addr::unchecked("admin11111"),
+            created_at: mock_env().block.time,
+        }).unwrap();
+        CLUB_OWNERSHIP_DETAILS.save(&mut deps.storage, raw.clone(), &ClubOwnershipDetails {
+            club_name: raw.clone(),
+            start_timestamp: mock_env().block.time,
+            locking_period: 0,
+            owner_address: "Owner001".to_string(),
+            price_paid: Uint128::from(CLUB_PRICE),
+            reward_amount: Uint128::from(3u128),
+            owner_released: false,
+        }).unwrap();
+        OWNER_CLUBS.save(&mut deps.storage, ("Owner001".to_string(), raw.clone()), &Empty {}).unwrap();
+        club_staking_details().save(&mut deps.storage, (raw.clone(), "Staker001".to_string()), &ClubStakingDetails {
+            club_name: raw.clone(),
+            staker_address: "Staker001".to_string(),
+            staked_amount: Uint128::from(50u128),
+            reward_amount: Uint128::zero(),
+            ..Default::default()
+        }).unwrap();
+        CLUB_STAKING_TOTALS.save(&mut deps.storage, raw.clone(), &Uint128::from(50u128)).unwrap();
+        CLUB_LEADERBOARD.save(&mut deps.storage, leaderboard_key(raw.clone(), Uint128::from(50u128)), &Uint128::from(50u128)).unwrap();
+        CLUB_REWARD_INDEX.save(&mut deps.storage, raw.clone(), &Decimal::percent(1)).unwrap();
+        CLUB_BONDING_DETAILS.save(&mut deps.storage, raw.clone(), &vec![ClubBondingDetails {
+            club_name: raw.clone(),
+            bonder_address: "Staker001".to_string(),
+            bonding_start_timestamp: mock_env().block.time,
+            bonded_amount: Uint128::from(7u128),
+            bonding_duration: 0,
+            ..Default::default()
+        }]).unwrap();
+        CLUB_PREVIOUS_OWNER_DETAILS.save(&mut deps.storage, (raw.clone(), "Owner000".to_string()), &ClubPreviousOwnerDetails {
+            club_name: raw.clone(),
+            previous_owner_address: "Owner000".to_string(),
+            reward_amount: Uint128::from(9u128),
+        }).unwrap();
+        append_ownership_record(&mut deps.storage, &raw, "Owner001".to_string(), mock_env().block.time,
+            Uint128::from(CLUB_PRICE), Uint128::from(3u128)).unwrap();
+        set_contract_version(&mut deps.storage, CONTRACT_NAME, "0.16.0").unwrap();
+
+        migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap();
+
+        assert_eq!(CLUBS.may_load(&deps.storage, raw.clone()).unwrap(), None);
+        assert_eq!(CLUBS.load(&deps.storage, normalized.clone()).unwrap().club_name, normalized);
+        let owner = query_club_ownership_details(&deps.storage, normalized.clone()).unwrap();
+        assert_eq!(owner.club_name, normalized);
+        assert!(CLUB_OWNERSHIP_DETAILS.may_load(&deps.storage, raw.clone()).unwrap().is_none());
+        assert!(OWNER_CLUBS.has(&deps.storage, ("Owner001".to_string(), normalized.clone())));
+        assert!(!OWNER_CLUBS.has(&deps.storage, ("Owner001".to_string(), raw.clone())));
+        let stakes = query_club_staking_details(&mut deps.storage, normalized.clone()).unwrap();
+        assert_eq!(stakes.len(), 1);
+        assert_eq!(stakes[0].club_name, normalized);
+        assert_eq!(club_staking_details().may_load(&deps.storage, (raw.clone(), "Staker001".to_string())).unwrap(), None);
+        assert_eq!(CLUB_STAKING_TOTALS.load(&deps.storage, normalized.clone()).unwrap(), Uint128::from(50u128));
+        assert_eq!(get_top_ranked_club(&deps.storage).unwrap(), Some((normalized.clone(), Uint128::from(50u128))));
+        assert_eq!(CLUB_REWARD_INDEX.load(&deps.storage, normalized.clone()).unwrap(), Decimal::percent(1));
+        let bonds = query_club_bonding_details(&deps.storage, normalized.clone()).unwrap();
+        assert_eq!(bonds[0].club_name, normalized);
+        assert_eq!(CLUB_BOND_SEQUENCE.load(&deps.storage, normalized.clone()).unwrap(), 1);
+        let previous = query_club_previous_owner_details(&deps.storage, normalized.clone(), "Owner000".to_string()).unwrap();
+        assert_eq!(previous.reward_amount, Uint128::from(9u128));
+        let record = CLUB_OWNERSHIP_HISTORY.load(&deps.storage, (normalized.clone(), U64Key::from(0))).unwrap();
+        assert_eq!(record.club_name, normalized);
+        assert_eq!(CLUB_OWNERSHIP_HISTORY_LENGTH.load(&deps.storage, normalized.clone()).unwrap(), 1);
+
+        // The portfolio indexes are built from the migrated bonds and balances
+        let portfolio = query_staker_portfolio(deps.as_ref(), "Staker001".to_string()).unwrap();
+        assert_eq!(portfolio.bonds[0].club_name, normalized);
+        assert_eq!(portfolio.total_bonded, Uint128::from(7u128));
+        let portfolio = query_staker_portfolio(deps.as_ref(), "Owner000".to_string()).unwrap();
+        assert_eq!(portfolio.previously_owned_clubs[0].club_name, normalized);
+        assert_eq!(portfolio.total_owner_rewards, Uint128::from(9u128));
+
+        // The club answers to any spelling of its name again
+        let owner: ClubOwnershipDetailsResponse = from_binary(&query(deps.as_ref(), mock_env(),
+            QueryMsg::ClubOwnershipDetails { club_name: "Club 005".to_string() }).unwrap()).unwrap();
+        assert_eq!(owner.owner_address, "Owner001".to_string());
+
+        // Two stored spellings of the same club cannot be merged
+        let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
+        let info = CLUBS.load(&deps.storage, "CLUB001".to_string()).unwrap();
+        CLUBS.save(&mut deps.storage, "club001".to_string(), &info).unwrap();
+        set_contract_version(&mut deps.storage, CONTRACT_NAME, "0.16.0").unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap_err();
+        assert_eq!(err, ContractError::ClubNameCollision {
+            club: "club001".to_string(),
+            normalized: "CLUB001".to_string(),
+        });
+    }
+
+    #[test]
+    fn test_migrate_keeps_legacy_club_names_breaking_the_name_rules() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
+        let info = CLUBS.load(&deps.storage, "CLUB001".to_string()).unwrap();
+        for raw in ["Man Utd.", "fc"] {
+            CLUBS.save(&mut deps.storage, raw.to_string(), &ClubInfo { club_name: raw.to_string(), ..info.clone() }).unwrap();
+            CLUB_STAKING_TOTALS.save(&mut deps.storage, raw.to_string(), &Uint128::from(50u128)).unwrap();
+        }
+        set_contract_version(&mut deps.storage, CONTRACT_NAME, "0.16.0").unwrap();
+
+        // The names are only brought into canonical form and reported
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap();
+        let reported: Vec<String> = res.attributes.iter()
+            .filter(|attr| attr.key == "invalid_club_name")
+            .map(|attr| attr.value.clone())
+            .collect();
+        assert_eq!(reported, vec!["FC", "MAN UTD."]);
+        assert_eq!(CLUBS.load(&deps.storage, "MAN UTD.".to_string()).unwrap().club_name, "MAN UTD.");
+        assert!(!CLUBS.has(&deps.storage, "Man Utd.".to_string()));
+        assert_eq!(CLUB_STAKING_TOTALS.load(&deps.storage, "FC".to_string()).unwrap(), Uint128::from(50u128));
+        assert_eq!(get_contract_version(&deps.storage).unwrap().version, CONTRACT_VERSION);
+    }
+
+    #[test]
+    fn test_dynamic_club_pricing() {
         let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
 
-        let owner1Info = mock_info("Owner001", &[coin(1000, "stake")]);
-        buy_a_club(deps.as_mut(), mock_env(), owner1Info.clone(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
-            Uint128::from(1000u128));
-
-        release_club(deps.as_mut(), mock_env(), owner1Info.clone(), "Owner001".to_string(), "CLUB001".to_string());
+        let adminInfo = mock_info("admin11111", &[]);
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            adminInfo,
+            ExecuteMsg::UpdateConfig {
+                treasury_address: None,
+                protocol_fee_percentage: None,
+                early_withdrawal_penalty_percentage: None,
+                penalty_destination: None,
+                club_price: None,
+                club_buying_reward_amount: None,
+                club_staking_reward_amount: None,
+                club_locking_duration: None,
+                club_bonding_duration: None,
+                owner_reward_percentage: None,
+                winner_club_reward_percentage: None,
+                all_stakers_reward_percentage: None,
+                club_creation_fee: None,
+                auction_extension_duration: None,
+                pricing_model: Some(PricingModel::Dynamic {
+                    resale_multiplier: Decimal::percent(150),
+                    stake_multiplier: Decimal::percent(10),
+                }),
+                max_clubs_per_owner: None,
+            },
+        )
+        .unwrap();
 
-        let queryRes = query_club_ownership_details(&mut deps.storage, "CLUB001".to_string());
-        match queryRes {
-            Ok(cod) => { 
-                assert_eq!(cod.owner_address, "Owner001".to_string());
-                assert_eq!(cod.price_paid, Uint128::from(1000u128));
-                assert_eq!(cod.owner_released, false);
-            }
-            Err(e) => {
-                println!("error parsing header: {:?}", e);
-                assert_eq!(1, 2);
-            }
-        }
+        let quote = |deps: Deps| -> ClubPriceResponse {
+            from_binary(&query(deps, mock_env(), QueryMsg::ClubPrice { club_name: "club001".to_string() }).unwrap()).unwrap()
+        };
+        assert_eq!(quote(deps.as_ref()).price, Uint128::from(CLUB_PRICE));
+        assert!(quote(deps.as_ref()).purchasable);
+
+        let owner1Info = mock_info("Owner001", &[]);
+        buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(), Uint128::from(CLUB_PRICE)).unwrap();
+        stake_on_a_club(deps.as_mut(), mock_env(), "Staker001".to_string(), "CLUB001".to_string(), Uint128::from(1000u128)).unwrap();
+
+        // Resale floor of 1.5 times the last price, plus a tenth of the stake
+        let expectedPrice = Uint128::from(CLUB_PRICE * 3 / 2 + 100);
+        let res = quote(deps.as_ref());
+        assert_eq!(res.price, expectedPrice);
+        assert_eq!(res.floor_price, expectedPrice);
+        // Owned and not released
+        assert!(!res.purchasable);
+
+        let mut cod = query_club_ownership_details(&mut deps.storage, "CLUB001".to_string()).unwrap();
+        cod.start_timestamp = mock_env().block.time.minus_seconds(22 * 24 * 60 * 60);
+        CLUB_OWNERSHIP_DETAILS.save(&mut deps.storage, "CLUB001".to_string(), &cod).unwrap();
+        release_club(deps.as_mut(), mock_env(), owner1Info.clone(), "Owner001".to_string(), "CLUB001".to_string()).unwrap();
+        assert!(quote(deps.as_ref()).purchasable);
+
+        // Nor can it be bought while in auction
+        execute(deps.as_mut(), mock_env(), owner1Info.clone(), ExecuteMsg::StartAuction {
+            club_name: "CLUB001".to_string(),
+            reserve_price: expectedPrice,
+            min_bid_increment: Uint128::from(100u128),
+            duration: 24 * 60 * 60,
+        }).unwrap();
+        assert!(!quote(deps.as_ref()).purchasable);
+        let mut endedEnv = mock_env();
+        endedEnv.block.time = endedEnv.block.time.plus_seconds(24 * 60 * 60);
+        settle_auction(deps.as_mut(), endedEnv, "CLUB001".to_string()).unwrap();
+        assert!(quote(deps.as_ref()).purchasable);
+
+        let err = buy_a_club(deps.as_mut(), mock_env(), "Owner002".to_string(), "Owner001".to_string(), "CLUB001".to_string(), Uint128::from(CLUB_PRICE)).unwrap_err();
+        assert_eq!(err, ContractError::InsufficientFunds { required: expectedPrice, sent: Uint128::from(CLUB_PRICE) });
+        buy_a_club(deps.as_mut(), mock_env(), "Owner002".to_string(), "Owner001".to_string(), "CLUB001".to_string(), expectedPrice).unwrap();
+
+        let cod = query_club_ownership_details(&mut deps.storage, "CLUB001".to_string()).unwrap();
+        assert_eq!(cod.owner_address, "Owner002".to_string());
+        assert_eq!(cod.price_paid, expectedPrice);
+        assert_eq!(quote(deps.as_ref()).price, expectedPrice * Decimal::percent(150) + Uint128::from(100u128));
+
+        // A price compounded past Uint128 is an error, not a panic
+        let mut cod = query_club_ownership_details(&deps.storage, "CLUB001".to_string()).unwrap();
+        cod.price_paid = Uint128::MAX;
+        CLUB_OWNERSHIP_DETAILS.save(&mut deps.storage, "CLUB001".to_string(), &cod).unwrap();
+        let config = CONFIG.load(&deps.storage).unwrap();
+        let err = floor_price(&deps.storage, &config, "CLUB001", Some(&cod)).unwrap_err();
+        assert_eq!(err, ContractError::PriceOverflow { club: "CLUB001".to_string() });
+        assert!(query(deps.as_ref(), mock_env(), QueryMsg::ClubPrice { club_name: "club001".to_string() }).is_err());
+        // or where only the stake term takes it over
+        cod.price_paid = Uint128::MAX.multiply_ratio(2u128, 3u128);
+        let err = floor_price(&deps.storage, &config, "CLUB001", Some(&cod)).unwrap_err();
+        assert_eq!(err, ContractError::PriceOverflow { club: "CLUB001".to_string() });
     }
 
     #[test]
-    fn test_releasing_of_club_after_locking_period () {
+    fn test_listings_and_offers() {
         let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
+
+        let owner1Info = mock_info("Owner001", &[]);
+        buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(), Uint128::from(CLUB_PRICE)).unwrap();
+        let list_msg = ExecuteMsg::ListClub {
+            club_name: "CLUB001".to_string(),
+            asking_price: Uint128::from(2 * CLUB_PRICE),
+            expiry: Some(Expiration::AtTime(mock_env().block.time.plus_seconds(24 * 60 * 60))),
+        };
+        let err = execute(deps.as_mut(), mock_env(), owner1Info.clone(), list_msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::ClubLocked { .. }));
+
+        let mut cod = query_club_ownership_details(&mut deps.storage, "CLUB001".to_string()).unwrap();
+        cod.start_timestamp = mock_env().block.time.minus_seconds(22 * 24 * 60 * 60);
+        CLUB_OWNERSHIP_DETAILS.save(&mut deps.storage, "CLUB001".to_string(), &cod).unwrap();
+
+        let err = execute(deps.as_mut(), mock_env(), mock_info("Owner002", &[]), list_msg.clone()).unwrap_err();
+        assert_eq!(err, ContractError::NotClubOwner { club: "CLUB001".to_string(), address: "Owner002".to_string() });
+        execute(deps.as_mut(), mock_env(), owner1Info.clone(), list_msg).unwrap();
+
+        // The listed club sells at the asking price, not the club price
+        let err = buy_a_club(deps.as_mut(), mock_env(), "Owner002".to_string(), "Owner001".to_string(), "CLUB001".to_string(), Uint128::from(CLUB_PRICE)).unwrap_err();
+        assert_eq!(err, ContractError::InsufficientFunds { required: Uint128::from(2 * CLUB_PRICE), sent: Uint128::from(CLUB_PRICE) });
+        let listings: ListingsResponse = from_binary(
+            &query(deps.as_ref(), mock_env(), QueryMsg::ClubListings { start_after: None, limit: None }).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(listings.listings.len(), 1);
+        let mut expiredEnv = mock_env();
+        expiredEnv.block.time = expiredEnv.block.time.plus_seconds(2 * 24 * 60 * 60);
+        let listings: ListingsResponse = from_binary(
+            &query(deps.as_ref(), expiredEnv, QueryMsg::ClubListings { start_after: None, limit: None }).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(listings.listings.len(), 0);
 
-        let owner1Info = mock_info("Owner001", &[coin(1000, "stake")]);
-        buy_a_club(deps.as_mut(), mock_env(), owner1Info.clone(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
-            Uint128::from(1000u128));
-
-        release_club(deps.as_mut(), mock_env(), owner1Info.clone(), "Owner001".to_string(), "CLUB001".to_string());
-
-        let now = mock_env().block.time; // today
-
-        let queryRes = query_club_ownership_details(&mut deps.storage, "CLUB001".to_string());
-        match queryRes {
-            Ok(mut cod) => { 
-                assert_eq!(cod.owner_address, "Owner001".to_string());
-                assert_eq!(cod.price_paid, Uint128::from(1000u128));
-                assert_eq!(cod.owner_released, false);
-                cod.start_timestamp = now.minus_seconds(22 * 24 * 60 * 60);
-                CLUB_OWNERSHIP_DETAILS.save(&mut deps.storage, "CLUB001".to_string(), &cod);
-            }
-            Err(e) => {
-                println!("error parsing header: {:?}", e);
-                assert_eq!(1, 2);
-            }
-        }
+        let tokenInfo = mock_info("cwtoken11111", &[]);
+        let offer_msg = |bidder: &str, amount: u128| ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: bidder.to_string(),
+            amount: Uint128::from(amount),
+            msg: to_binary(&ReceiveMsg::MakeOffer { club_name: "CLUB001".to_string(), expiry: None }).unwrap(),
+        });
+        execute(deps.as_mut(), mock_env(), tokenInfo.clone(), offer_msg("Bidder001", CLUB_PRICE)).unwrap();
+        execute(deps.as_mut(), mock_env(), tokenInfo.clone(), offer_msg("Bidder001", 500)).unwrap();
+        execute(deps.as_mut(), mock_env(), tokenInfo.clone(), offer_msg("Bidder002", 700)).unwrap();
+        let offers: OffersResponse = from_binary(
+            &query(deps.as_ref(), mock_env(), QueryMsg::ClubOffers { club_name: "club001".to_string(), start_after: None, limit: None }).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(offers.offers.len(), 2);
+        assert_eq!(offers.offers[0].amount, Uint128::from(CLUB_PRICE + 500));
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("Bidder002", &[]), ExecuteMsg::WithdrawOffer { club_name: "CLUB001".to_string() }).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: "cwtoken11111".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "Bidder002".to_string(),
+                    amount: Uint128::from(700u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })]
+        );
 
-        release_club(deps.as_mut(), mock_env(), owner1Info.clone(), "Owner001".to_string(), "CLUB001".to_string());
+        let accept_msg = |bidder: &str| ExecuteMsg::AcceptOffer { club_name: "CLUB001".to_string(), bidder: bidder.to_string() };
+        let err = execute(deps.as_mut(), mock_env(), owner1Info.clone(), accept_msg("Bidder002")).unwrap_err();
+        assert_eq!(err, ContractError::NoOffer { club: "CLUB001".to_string(), bidder: "Bidder002".to_string() });
+        execute(deps.as_mut(), mock_env(), owner1Info.clone(), accept_msg("Bidder001")).unwrap();
+
+        let cod = query_club_ownership_details(&mut deps.storage, "CLUB001".to_string()).unwrap();
+        assert_eq!(cod.owner_address, "Bidder001".to_string());
+        assert_eq!(cod.price_paid, Uint128::from(CLUB_PRICE + 500));
+        assert_eq!(cod.owner_released, false);
+        let previous = CLUB_PREVIOUS_OWNER_DETAILS.load(&deps.storage, ("CLUB001".to_string(), "Owner001".to_string())).unwrap();
+        assert_eq!(previous.previous_owner_address, "Owner001".to_string());
+        assert!(!CLUB_LISTINGS.has(&deps.storage, "CLUB001".to_string()));
+        assert!(!CLUB_OFFERS.has(&deps.storage, ("CLUB001".to_string(), "Bidder001".to_string())));
+
+        let err = execute(deps.as_mut(), mock_env(), mock_info("Bidder001", &[]), ExecuteMsg::CancelListing { club_name: "CLUB001".to_string() }).unwrap_err();
+        assert_eq!(err, ContractError::ClubNotReleased { club: "CLUB001".to_string() });
+    }
 
-        let queryResAfterReleasing = query_club_ownership_details(&mut deps.storage, "CLUB001".to_string());
-        match queryResAfterReleasing {
-            Ok(cod) => { 
-                assert_eq!(cod.owner_address, "Owner001".to_string());
-                assert_eq!(cod.price_paid, Uint128::from(1000u128));
-                assert_eq!(cod.owner_released, true);
-            }
-            Err(e) => {
-                println!("error parsing header: {:?}", e);
-                assert_eq!(1, 2);
-            }
+    #[test]
+    fn test_expired_listing_no_longer_blocks_the_club() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
+
+        let owner1Info = mock_info("Owner001", &[]);
+        for club_name in ["CLUB001", "CLUB002"] {
+            buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), club_name.to_string(), Uint128::from(CLUB_PRICE)).unwrap();
+            let mut cod = query_club_ownership_details(&mut deps.storage, club_name.to_string()).unwrap();
+            cod.start_timestamp = mock_env().block.time.minus_seconds(22 * 24 * 60 * 60);
+            CLUB_OWNERSHIP_DETAILS.save(&mut deps.storage, club_name.to_string(), &cod).unwrap();
+            execute(deps.as_mut(), mock_env(), owner1Info.clone(), ExecuteMsg::ListClub {
+                club_name: club_name.to_string(),
+                asking_price: Uint128::from(2 * CLUB_PRICE),
+                expiry: Some(Expiration::AtTime(mock_env().block.time.plus_seconds(24 * 60 * 60))),
+            }).unwrap();
         }
+        let mut expiredEnv = mock_env();
+        expiredEnv.block.time = expiredEnv.block.time.plus_seconds(2 * 24 * 60 * 60);
+
+        // The club is off sale once its listing has expired
+        let price: ClubPriceResponse = from_binary(&query(deps.as_ref(), expiredEnv.clone(),
+            QueryMsg::ClubPrice { club_name: "CLUB001".to_string() }).unwrap()).unwrap();
+        assert!(!price.purchasable);
+        let err = buy_a_club(deps.as_mut(), expiredEnv.clone(), "Owner002".to_string(), "Owner001".to_string(), "CLUB001".to_string(), Uint128::from(2 * CLUB_PRICE)).unwrap_err();
+        assert_eq!(err, ContractError::ClubNotReleased { club: "CLUB001".to_string() });
+        let err = execute(deps.as_mut(), expiredEnv.clone(), owner1Info.clone(), ExecuteMsg::StartAuction {
+            club_name: "CLUB001".to_string(),
+            reserve_price: Uint128::from(CLUB_PRICE),
+            min_bid_increment: Uint128::from(100u128),
+            duration: 24 * 60 * 60,
+        }).unwrap_err();
+        assert_eq!(err, ContractError::ClubNotReleased { club: "CLUB001".to_string() });
+
+        // until the owner releases it again
+        release_club(deps.as_mut(), expiredEnv.clone(), owner1Info.clone(), "Owner001".to_string(), "CLUB002".to_string()).unwrap();
+        execute(deps.as_mut(), expiredEnv, owner1Info.clone(), ExecuteMsg::StartAuction {
+            club_name: "CLUB002".to_string(),
+            reserve_price: Uint128::from(CLUB_PRICE),
+            min_bid_increment: Uint128::from(100u128),
+            duration: 24 * 60 * 60,
+        }).unwrap();
+        assert!(!CLUB_LISTINGS.has(&deps.storage, "CLUB002".to_string()));
+
+        // Offers cannot be accepted for a paused club
+        buy_a_club(deps.as_mut(), mock_env(), "Owner003".to_string(), "".to_string(), "CLUB003".to_string(), Uint128::from(CLUB_PRICE)).unwrap();
+        let mut cod = query_club_ownership_details(&mut deps.storage, "CLUB003".to_string()).unwrap();
+        cod.start_timestamp = mock_env().block.time.minus_seconds(22 * 24 * 60 * 60);
+        CLUB_OWNERSHIP_DETAILS.save(&mut deps.storage, "CLUB003".to_string(), &cod).unwrap();
+        make_offer(deps.as_mut(), mock_env(), "Bidder001".to_string(), "CLUB003".to_string(), Uint128::from(CLUB_PRICE), None).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("admin11111", &[]), ExecuteMsg::SetClubStatus {
+            club_name: "CLUB003".to_string(),
+            status: ClubStatus::Paused,
+        }).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), mock_info("Owner003", &[]), ExecuteMsg::AcceptOffer {
+            club_name: "CLUB003".to_string(),
+            bidder: "Bidder001".to_string(),
+        }).unwrap_err();
+        assert_eq!(err, ContractError::ClubNotActive { club: "CLUB003".to_string(), status: ClubStatus::Paused });
     }
 
     #[test]
     fn test_buying_of_club_after_releasing_by_prev_owner () {
         let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
 
         let owner1Info = mock_info("Owner001", &[coin(1000, "stake")]);
-        buy_a_club(deps.as_mut(), mock_env(), owner1Info.clone(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
-            Uint128::from(1000u128));
+        buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
+            Uint128::from(CLUB_PRICE));
 
         release_club(deps.as_mut(), mock_env(), owner1Info.clone(), "Owner001".to_string(), "CLUB001".to_string());
 
@@ -1187,7 +4377,7 @@ mod tests {
         match queryRes {
             Ok(mut cod) => { 
                 assert_eq!(cod.owner_address, "Owner001".to_string());
-                assert_eq!(cod.price_paid, Uint128::from(1000u128));
+                assert_eq!(cod.price_paid, Uint128::from(CLUB_PRICE));
                 cod.start_timestamp = now.minus_seconds(22 * 24 * 60 * 60);
                 CLUB_OWNERSHIP_DETAILS.save(&mut deps.storage, "CLUB001".to_string(), &cod);
             }
@@ -1203,7 +4393,7 @@ mod tests {
         match queryResAfterReleasing {
             Ok(cod) => { 
                 assert_eq!(cod.owner_address, "Owner001".to_string());
-                assert_eq!(cod.price_paid, Uint128::from(1000u128));
+                assert_eq!(cod.price_paid, Uint128::from(CLUB_PRICE));
                 assert_eq!(cod.owner_released, true);
             }
             Err(e) => {
@@ -1213,14 +4403,14 @@ mod tests {
         }
 
         let owner2Info = mock_info("Owner002", &[coin(1000, "stake")]);
-        buy_a_club(deps.as_mut(), mock_env(), owner2Info.clone(), "Owner002".to_string(), "Owner001".to_string(), "CLUB001".to_string(),
-            Uint128::from(1000u128));
+        buy_a_club(deps.as_mut(), mock_env(), "Owner002".to_string(), "Owner001".to_string(), "CLUB001".to_string(),
+            Uint128::from(CLUB_PRICE));
 
         let queryResAfterSellingByPrevOwner = query_club_ownership_details(&mut deps.storage, "CLUB001".to_string());
         match queryResAfterSellingByPrevOwner {
             Ok(cod) => { 
                 assert_eq!(cod.owner_address, "Owner002".to_string());
-                assert_eq!(cod.price_paid, Uint128::from(1000u128));
+                assert_eq!(cod.price_paid, Uint128::from(CLUB_PRICE));
                 assert_eq!(cod.owner_released, false);
             }
             Err(e) => {
@@ -1232,11 +4422,12 @@ mod tests {
 
     #[test]
     fn test_claim_previous_owner_rewards () {
-        let mut deps = mock_dependencies(&[]);
+        let mut deps = mock_dependencies_with_token_balance(Uint128::from(1000000u128));
+        instantiate_contract(deps.as_mut());
 
         let owner1Info = mock_info("Owner001", &[coin(1000, "stake")]);
-        buy_a_club(deps.as_mut(), mock_env(), owner1Info.clone(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
-            Uint128::from(1000u128));
+        buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
+            Uint128::from(CLUB_PRICE));
 
         release_club(deps.as_mut(), mock_env(), owner1Info.clone(), "Owner001".to_string(), "CLUB001".to_string());
 
@@ -1246,7 +4437,7 @@ mod tests {
         match queryRes {
             Ok(mut cod) => { 
                 assert_eq!(cod.owner_address, "Owner001".to_string());
-                assert_eq!(cod.price_paid, Uint128::from(1000u128));
+                assert_eq!(cod.price_paid, Uint128::from(CLUB_PRICE));
                 cod.start_timestamp = now.minus_seconds(22 * 24 * 60 * 60);
                 CLUB_OWNERSHIP_DETAILS.save(&mut deps.storage, "CLUB001".to_string(), &cod);
             }
@@ -1262,7 +4453,7 @@ mod tests {
         match queryResAfterReleasing {
             Ok(cod) => { 
                 assert_eq!(cod.owner_address, "Owner001".to_string());
-                assert_eq!(cod.price_paid, Uint128::from(1000u128));
+                assert_eq!(cod.price_paid, Uint128::from(CLUB_PRICE));
                 assert_eq!(cod.owner_released, true);
             }
             Err(e) => {
@@ -1272,14 +4463,14 @@ mod tests {
         }
 
         let owner2Info = mock_info("Owner002", &[coin(1000, "stake")]);
-        buy_a_club(deps.as_mut(), mock_env(), owner2Info.clone(), "Owner002".to_string(), "Owner001".to_string(), "CLUB001".to_string(),
-            Uint128::from(1000u128));
+        buy_a_club(deps.as_mut(), mock_env(), "Owner002".to_string(), "Owner001".to_string(), "CLUB001".to_string(),
+            Uint128::from(CLUB_PRICE));
 
         let queryResAfterSellingByPrevOwner = query_club_ownership_details(&mut deps.storage, "CLUB001".to_string());
         match queryResAfterSellingByPrevOwner {
             Ok(cod) => { 
                 assert_eq!(cod.owner_address, "Owner002".to_string());
-                assert_eq!(cod.price_paid, Uint128::from(1000u128));
+                assert_eq!(cod.price_paid, Uint128::from(CLUB_PRICE));
                 assert_eq!(cod.owner_released, false);
             }
             Err(e) => {
@@ -1288,8 +4479,8 @@ mod tests {
             }
         }
 
-        claim_previous_owner_rewards(deps.as_mut(), owner1Info.clone(), "Owner001".to_string(), "CLUB001".to_string(), Uint128::from(10u128));
-        let queryPrevOwnerDetailsAfterRewardClaim = query_club_previous_owner_details(&mut deps.storage, "CLUB001".to_string());
+        claim_previous_owner_rewards(deps.as_mut(), mock_env(), owner1Info.clone(), "Owner001".to_string(), "CLUB001".to_string(), Uint128::from(10u128));
+        let queryPrevOwnerDetailsAfterRewardClaim = query_club_previous_owner_details(&mut deps.storage, "CLUB001".to_string(), "Owner001".to_string());
         match queryPrevOwnerDetailsAfterRewardClaim {
             Ok(pod) => { 
                 assert_eq!(pod.club_name, "CLUB001".to_string());
@@ -1306,17 +4497,18 @@ mod tests {
     #[test]
     fn test_multiple_staking_on_club_by_same_address () {
         let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
 
         let owner1Info = mock_info("Owner001", &[coin(1000, "stake")]);
-        buy_a_club(deps.as_mut(), mock_env(), owner1Info.clone(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
-            Uint128::from(1000u128));
+        buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
+            Uint128::from(CLUB_PRICE));
 
         let stakerInfo = mock_info("Staker0001", &[coin(10, "stake")]);
-        stake_on_a_club(deps.as_mut(), mock_env(), stakerInfo.clone(), "Staker0001".to_string(), "CLUB001".to_string(), 
+        stake_on_a_club(deps.as_mut(), mock_env(), "Staker0001".to_string(), "CLUB001".to_string(), 
             Uint128::from(33u128));
-        stake_on_a_club(deps.as_mut(), mock_env(), stakerInfo.clone(), "Staker0001".to_string(), "CLUB001".to_string(), 
+        stake_on_a_club(deps.as_mut(), mock_env(), "Staker0001".to_string(), "CLUB001".to_string(), 
             Uint128::from(11u128));
-        stake_on_a_club(deps.as_mut(), mock_env(), stakerInfo.clone(), "Staker0001".to_string(), "CLUB001".to_string(), 
+        stake_on_a_club(deps.as_mut(), mock_env(), "Staker0001".to_string(), "CLUB001".to_string(), 
             Uint128::from(42u128));
 
         let queryRes = query_all_stakes(&mut deps.storage);
@@ -1337,13 +4529,14 @@ mod tests {
     #[test]
     fn test_immediate_partial_withdrawals_from_club () {
         let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
 
         let owner1Info = mock_info("Owner001", &[coin(1000, "stake")]);
-        buy_a_club(deps.as_mut(), mock_env(), owner1Info.clone(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
-            Uint128::from(1000u128));
+        buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
+            Uint128::from(CLUB_PRICE));
 
         let stakerInfo = mock_info("Staker0001", &[coin(10, "stake")]);
-        stake_on_a_club(deps.as_mut(), mock_env(), stakerInfo.clone(), "Staker0001".to_string(), "CLUB001".to_string(), 
+        stake_on_a_club(deps.as_mut(), mock_env(), "Staker0001".to_string(), "CLUB001".to_string(), 
             Uint128::from(99u128));
         withdraw_stake_from_a_club(deps.as_mut(), mock_env(), stakerInfo.clone(), "Staker0001".to_string(), 
             "CLUB001".to_string(), Uint128::from(11u128), IMMEDIATE_WITHDRAWAL);
@@ -1366,7 +4559,7 @@ mod tests {
             }
         }
 
-        let queryBonds = query_all_bonds(&mut deps.storage);
+        let queryBonds = query_all_bonds(deps.as_ref(), None, None, None, None, None).map(|res| res.bonds);
         match queryBonds {
             Ok(all_bonds) => { 
                 assert_eq!(all_bonds.len(), 0);
@@ -1381,13 +4574,14 @@ mod tests {
     #[test]
     fn test_immediate_complete_withdrawals_from_club () {
         let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
 
         let owner1Info = mock_info("Owner001", &[coin(1000, "stake")]);
-        buy_a_club(deps.as_mut(), mock_env(), owner1Info.clone(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
-            Uint128::from(1000u128));
+        buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
+            Uint128::from(CLUB_PRICE));
 
         let stakerInfo = mock_info("Staker0001", &[coin(10, "stake")]);
-        stake_on_a_club(deps.as_mut(), mock_env(), stakerInfo.clone(), "Staker0001".to_string(), "CLUB001".to_string(), 
+        stake_on_a_club(deps.as_mut(), mock_env(), "Staker0001".to_string(), "CLUB001".to_string(), 
             Uint128::from(99u128));
         withdraw_stake_from_a_club(deps.as_mut(), mock_env(), stakerInfo.clone(), "Staker0001".to_string(), 
             "CLUB001".to_string(), Uint128::from(11u128), IMMEDIATE_WITHDRAWAL);
@@ -1409,7 +4603,7 @@ mod tests {
             }
         }
 
-        let queryBonds = query_all_bonds(&mut deps.storage);
+        let queryBonds = query_all_bonds(deps.as_ref(), None, None, None, None, None).map(|res| res.bonds);
         match queryBonds {
             Ok(all_bonds) => { 
                 assert_eq!(all_bonds.len(), 0);
@@ -1424,13 +4618,14 @@ mod tests {
     #[test]
     fn test_non_immediate_complete_withdrawals_from_club () {
         let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
 
         let owner1Info = mock_info("Owner001", &[coin(1000, "stake")]);
-        buy_a_club(deps.as_mut(), mock_env(), owner1Info.clone(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
-            Uint128::from(1000u128));
+        buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
+            Uint128::from(CLUB_PRICE));
 
         let stakerInfo = mock_info("Staker0001", &[coin(10, "stake")]);
-        stake_on_a_club(deps.as_mut(), mock_env(), stakerInfo.clone(), "Staker0001".to_string(), "CLUB001".to_string(), 
+        stake_on_a_club(deps.as_mut(), mock_env(), "Staker0001".to_string(), "CLUB001".to_string(), 
             Uint128::from(99u128));
         withdraw_stake_from_a_club(deps.as_mut(), mock_env(), stakerInfo.clone(), "Staker0001".to_string(), 
             "CLUB001".to_string(), Uint128::from(11u128), NO_IMMEDIATE_WITHDRAWAL);
@@ -1452,7 +4647,7 @@ mod tests {
             }
         }
 
-        let queryBonds = query_all_bonds(&mut deps.storage);
+        let queryBonds = query_all_bonds(deps.as_ref(), None, None, None, None, None).map(|res| res.bonds);
         match queryBonds {
             Ok(all_bonds) => { 
                 assert_eq!(all_bonds.len(), 4);
@@ -1476,13 +4671,14 @@ mod tests {
     #[test]
     fn test_non_immediate_complete_withdrawals_from_club_with_scheduled_refunds () {
         let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
 
         let owner1Info = mock_info("Owner001", &[coin(1000, "stake")]);
-        buy_a_club(deps.as_mut(), mock_env(), owner1Info.clone(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
-            Uint128::from(1000u128));
+        buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
+            Uint128::from(CLUB_PRICE));
 
         let stakerInfo = mock_info("Staker0001", &[coin(10, "stake")]);
-        stake_on_a_club(deps.as_mut(), mock_env(), stakerInfo.clone(), "Staker0001".to_string(), "CLUB001".to_string(), 
+        stake_on_a_club(deps.as_mut(), mock_env(), "Staker0001".to_string(), "CLUB001".to_string(), 
             Uint128::from(99u128));
         withdraw_stake_from_a_club(deps.as_mut(), mock_env(), stakerInfo.clone(), "Staker0001".to_string(), 
             "CLUB001".to_string(), Uint128::from(11u128), NO_IMMEDIATE_WITHDRAWAL);
@@ -1506,7 +4702,7 @@ mod tests {
 
         let now = mock_env().block.time; // today
 
-        let queryBonds = query_all_bonds(&mut deps.storage);
+        let queryBonds = query_all_bonds(deps.as_ref(), None, None, None, None, None).map(|res| res.bonds);
         match queryBonds {
             Ok(all_bonds) => { 
                 let existing_bonds = all_bonds.clone();
@@ -1535,10 +4731,26 @@ mod tests {
         }
 
 
-        let refunderInfo = mock_info(MAIN_WALLET, &[coin(1000, "stake")]);
-        periodically_refund_stakeouts(deps.as_mut(), mock_env(), refunderInfo);
-
-        let queryBondsAfterPeriodicRefund = query_all_bonds(&mut deps.storage);
+        let refunderInfo = mock_info("admin11111", &[coin(1000, "stake")]);
+        let res = periodically_refund_stakeouts(deps.as_mut(), mock_env(), refunderInfo, None, None).unwrap();
+        assert_eq!(res.messages, vec![SubMsg::new(WasmMsg::Execute {
+            contract_addr: "cwtoken11111".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "Staker0001".to_string(),
+                amount: Uint128::from(63u128),
+            }).unwrap(),
+            funds: vec![],
+        })]);
+        assert_eq!(res.attributes, vec![
+            attr("action", "periodically_refund_stakeouts"),
+            attr("bonds_settled", "1"),
+            attr("bonds_skipped", "0"),
+            attr("total_refunded", "63"),
+        ]);
+        let wallet = CONTRACT_WALLET.load(&deps.storage, &Addr::unchecked("Staker0001")).unwrap();
+        assert_eq!(wallet, Uint128::from(36u128));
+
+        let queryBondsAfterPeriodicRefund = query_all_bonds(deps.as_ref(), None, None, None, None, None).map(|res| res.bonds);
         match queryBondsAfterPeriodicRefund {
             Ok(all_bonds) => { 
                 assert_eq!(all_bonds.len(), 3);
@@ -1561,13 +4773,14 @@ mod tests {
     #[test]
     fn test_non_immediate_partial_withdrawals_from_club () {
         let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
 
         let owner1Info = mock_info("Owner001", &[coin(1000, "stake")]);
-        buy_a_club(deps.as_mut(), mock_env(), owner1Info.clone(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
-            Uint128::from(1000u128));
+        buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
+            Uint128::from(CLUB_PRICE));
 
         let stakerInfo = mock_info("Staker0001", &[coin(10, "stake")]);
-        stake_on_a_club(deps.as_mut(), mock_env(), stakerInfo.clone(), "Staker0001".to_string(), "CLUB001".to_string(), 
+        stake_on_a_club(deps.as_mut(), mock_env(), "Staker0001".to_string(), "CLUB001".to_string(), 
             Uint128::from(99u128));
         withdraw_stake_from_a_club(deps.as_mut(), mock_env(), stakerInfo.clone(), "Staker0001".to_string(), 
             "CLUB001".to_string(), Uint128::from(11u128), NO_IMMEDIATE_WITHDRAWAL);
@@ -1590,7 +4803,7 @@ mod tests {
             }
         }
 
-        let queryBonds = query_all_bonds(&mut deps.storage);
+        let queryBonds = query_all_bonds(deps.as_ref(), None, None, None, None, None).map(|res| res.bonds);
         match queryBonds {
             Ok(all_bonds) => { 
                 assert_eq!(all_bonds.len(), 3);
@@ -1613,48 +4826,50 @@ mod tests {
     #[test]
     fn test_distribute_rewards() {
         let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
 
         let owner1Info = mock_info("Owner001", &[coin(1000, "stake")]);
-        buy_a_club(deps.as_mut(), mock_env(), owner1Info.clone(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
-            Uint128::from(1000u128));
+        buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
+            Uint128::from(CLUB_PRICE));
         let owner2Info = mock_info("Owner002", &[coin(1000, "stake")]); 
-        buy_a_club(deps.as_mut(), mock_env(), owner2Info.clone(), "Owner002".to_string(), "".to_string(), "CLUB002".to_string(),
-            Uint128::from(1000u128));
+        buy_a_club(deps.as_mut(), mock_env(), "Owner002".to_string(), "".to_string(), "CLUB002".to_string(),
+            Uint128::from(CLUB_PRICE));
         let owner3Info = mock_info("Owner003", &[coin(1000, "stake")]);
-        buy_a_club(deps.as_mut(), mock_env(), owner3Info.clone(), "Owner003".to_string(), "".to_string(), "CLUB003".to_string(),
-            Uint128::from(1000u128));
+        buy_a_club(deps.as_mut(), mock_env(), "Owner003".to_string(), "".to_string(), "CLUB003".to_string(),
+            Uint128::from(CLUB_PRICE));
 
         let staker1Info = mock_info("Staker0001", &[coin(10, "stake")]);
-        stake_on_a_club(deps.as_mut(), mock_env(), staker1Info.clone(), "Staker0001".to_string(), "CLUB001".to_string(), 
+        stake_on_a_club(deps.as_mut(), mock_env(), "Staker0001".to_string(), "CLUB001".to_string(), 
             Uint128::from(330000u128));
 
         let staker2Info = mock_info("Staker0002", &[coin(10, "stake")]);
-        stake_on_a_club(deps.as_mut(), mock_env(), staker2Info.clone(), "Staker0002".to_string(), "CLUB001".to_string(), 
+        stake_on_a_club(deps.as_mut(), mock_env(), "Staker0002".to_string(), "CLUB001".to_string(), 
             Uint128::from(110000u128));
 
         let staker3Info = mock_info("Staker0003", &[coin(10, "stake")]);
-        stake_on_a_club(deps.as_mut(), mock_env(), staker3Info.clone(), "Staker0003".to_string(), "CLUB002".to_string(), 
+        stake_on_a_club(deps.as_mut(), mock_env(), "Staker0003".to_string(), "CLUB002".to_string(), 
             Uint128::from(420000u128));
 
         let staker4Info = mock_info("Staker0004", &[coin(10, "stake")]);
-        stake_on_a_club(deps.as_mut(), mock_env(), staker4Info.clone(), "Staker0004".to_string(), "CLUB002".to_string(), 
+        stake_on_a_club(deps.as_mut(), mock_env(), "Staker0004".to_string(), "CLUB002".to_string(), 
             Uint128::from(100000u128));
 
         let staker5Info = mock_info("Staker0005", &[coin(10, "stake")]);
-        stake_on_a_club(deps.as_mut(), mock_env(), staker5Info.clone(), "Staker0005".to_string(), "CLUB003".to_string(), 
+        stake_on_a_club(deps.as_mut(), mock_env(), "Staker0005".to_string(), "CLUB003".to_string(), 
             Uint128::from(820000u128));
 
         let staker6Info = mock_info("Staker0006", &[coin(10, "stake")]);
-        stake_on_a_club(deps.as_mut(), mock_env(), staker6Info.clone(), "Staker0006".to_string(), "CLUB003".to_string(), 
+        stake_on_a_club(deps.as_mut(), mock_env(), "Staker0006".to_string(), "CLUB003".to_string(), 
             Uint128::from(50000u128));
 
-        let instantiate_msg = InstantiateMsg {
-            cw20_token_address: "cwtoken11111".to_string(),
-            admin_address: "admin11111".to_string(),
-        };
         let rewardInfo = mock_info("rewardInfo", &[]);
-        instantiate(deps.as_mut(), mock_env(), rewardInfo.clone(), instantiate_msg).unwrap();
-        set_reward_amount(deps.as_mut(), rewardInfo.clone(), Uint128::from(1000000u128));
+        let err = set_reward_amount(deps.as_mut(), mock_env(), rewardInfo.clone(), Uint128::from(1000000u128)).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        execute(deps.as_mut(), mock_env(), mock_info("admin11111", &[]), ExecuteMsg::GrantRole {
+            address: "rewardInfo".to_string(),
+            role: Role::RewardDistributor,
+        }).unwrap();
+        set_reward_amount(deps.as_mut(), mock_env(), rewardInfo.clone(), Uint128::from(1000000u128));
 
         let res = execute(
             deps.as_mut(),
@@ -1663,7 +4878,16 @@ mod tests {
             ExecuteMsg::CalculateAndDistributeRewards { },
         )
         .unwrap();
-        assert_eq!(res, Response::default());
+        assert_eq!(res.events, vec![
+            Event::new("rewards_distributed")
+                .add_attribute("timestamp", mock_env().block.time.seconds().to_string())
+                .add_attribute("club", "CLUB003")
+                .add_attribute("address", "Owner003")
+                .add_attribute("amount", "1000000")
+                .add_attribute("owner_reward", "10000")
+                .add_attribute("winner_club_reward", "190000")
+                .add_attribute("all_stakers_reward", "800000"),
+        ]);
 
         let queryRes = query_all_stakes(&mut deps.storage);
         match queryRes {
@@ -1709,4 +4933,1140 @@ mod tests {
             total reward given Uint128(999995) out of Uint128(1000000)
         */
     }
+
+    #[test]
+    fn test_rewards_are_settled_lazily() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
+
+        buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
+            Uint128::from(CLUB_PRICE)).unwrap();
+        stake_on_a_club(deps.as_mut(), mock_env(), "Staker0001".to_string(), "CLUB001".to_string(),
+            Uint128::from(100000u128)).unwrap();
+
+        let adminInfo = mock_info("admin11111", &[]);
+        set_reward_amount(deps.as_mut(), mock_env(), adminInfo.clone(), Uint128::from(1000u128)).unwrap();
+        calculate_and_distribute_rewards(deps.as_mut(), mock_env(), adminInfo.clone()).unwrap();
+
+        // a stake placed after the distribution does not share in it
+        stake_on_a_club(deps.as_mut(), mock_env(), "Staker0002".to_string(), "CLUB001".to_string(),
+            Uint128::from(100000u128)).unwrap();
+        // withdrawing everything keeps the earned reward claimable
+        withdraw_stake_from_a_club(deps.as_mut(), mock_env(), mock_info("Staker0001", &[]),
+            "Staker0001".to_string(), "CLUB001".to_string(), Uint128::from(100000u128), IMMEDIATE_WITHDRAWAL)
+            .unwrap();
+
+        let stakes = query_club_staking_details(&mut deps.storage, "CLUB001".to_string()).unwrap();
+        assert_eq!(stakes.len(), 2);
+        assert_eq!(stakes[0].staker_address, "Staker0001");
+        assert_eq!(stakes[0].staked_amount, Uint128::zero());
+        assert_eq!(stakes[0].reward_amount, Uint128::from(990u128));
+        assert_eq!(stakes[1].staker_address, "Staker0002");
+        assert_eq!(stakes[1].reward_amount, Uint128::zero());
+
+        // the next distribution is shared by the remaining stake only
+        set_reward_amount(deps.as_mut(), mock_env(), adminInfo.clone(), Uint128::from(1000u128)).unwrap();
+        calculate_and_distribute_rewards(deps.as_mut(), mock_env(), adminInfo).unwrap();
+        let stakes = query_club_staking_details(&mut deps.storage, "CLUB001".to_string()).unwrap();
+        assert_eq!(stakes[0].reward_amount, Uint128::from(990u128));
+        assert_eq!(stakes[1].reward_amount, Uint128::from(990u128));
+    }
+
+    #[test]
+    fn test_club_ranking_is_kept_up_to_date_and_paginated() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
+
+        for (club, amount) in [("CLUB001", 300u128), ("CLUB002", 500u128), ("CLUB003", 300u128), ("CLUB004", 100u128)] {
+            buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), club.to_string(),
+                Uint128::from(CLUB_PRICE)).unwrap();
+            stake_on_a_club(deps.as_mut(), mock_env(), "Staker0001".to_string(), club.to_string(),
+                Uint128::from(amount)).unwrap();
+        }
+        // CLUB004 overtakes everyone, CLUB002 falls behind the tie
+        stake_on_a_club(deps.as_mut(), mock_env(), "Staker0002".to_string(), "CLUB004".to_string(),
+            Uint128::from(900u128)).unwrap();
+        withdraw_stake_from_a_club(deps.as_mut(), mock_env(), mock_info("Staker0001", &[]),
+            "Staker0001".to_string(), "CLUB002".to_string(), Uint128::from(400u128), IMMEDIATE_WITHDRAWAL)
+            .unwrap();
+
+        let page: ClubRankingResponse = from_binary(&query(deps.as_ref(), mock_env(),
+            QueryMsg::GetClubRankingByStakes { start_after: None, limit: Some(2) }).unwrap()).unwrap();
+        assert_eq!(page.clubs, vec![
+            ClubRank { rank: 1, club_name: "CLUB004".to_string(), total_staked: Uint128::from(1000u128) },
+            ClubRank { rank: 2, club_name: "CLUB001".to_string(), total_staked: Uint128::from(300u128) },
+        ]);
+        let page: ClubRankingResponse = from_binary(&query(deps.as_ref(), mock_env(),
+            QueryMsg::GetClubRankingByStakes { start_after: Some(("CLUB001".to_string(), 2)), limit: None }).unwrap()).unwrap();
+        assert_eq!(page.clubs, vec![
+            ClubRank { rank: 3, club_name: "CLUB003".to_string(), total_staked: Uint128::from(300u128) },
+            ClubRank { rank: 4, club_name: "CLUB002".to_string(), total_staked: Uint128::from(100u128) },
+        ]);
+        let samePage: ClubRankingResponse = from_binary(&query(deps.as_ref(), mock_env(),
+            QueryMsg::GetClubRankingByStakes { start_after: Some(("club001".to_string(), 2)), limit: None }).unwrap()).unwrap();
+        assert_eq!(samePage.clubs, page.clubs);
+    }
+
+    #[test]
+    fn test_paginated_and_filtered_listings() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
+
+        buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
+            Uint128::from(CLUB_PRICE)).unwrap();
+        buy_a_club(deps.as_mut(), mock_env(), "Owner002".to_string(), "".to_string(), "CLUB002".to_string(),
+            Uint128::from(CLUB_PRICE)).unwrap();
+        for (club, staker, amount) in [
+            ("CLUB001", "Staker0001", 10u128),
+            ("CLUB001", "Staker0002", 20u128),
+            ("CLUB001", "Staker0003", 30u128),
+            ("CLUB002", "Staker0001", 40u128),
+        ] {
+            stake_on_a_club(deps.as_mut(), mock_env(), staker.to_string(), club.to_string(),
+                Uint128::from(amount)).unwrap();
+        }
+        for club in ["CLUB001", "CLUB002"] {
+            withdraw_stake_from_a_club(deps.as_mut(), mock_env(), mock_info("Staker0001", &[]),
+                "Staker0001".to_string(), club.to_string(), Uint128::from(5u128), NO_IMMEDIATE_WITHDRAWAL)
+                .unwrap();
+        }
+        withdraw_stake_from_a_club(deps.as_mut(), mock_env(), mock_info("Staker0003", &[]),
+            "Staker0003".to_string(), "CLUB001".to_string(), Uint128::from(5u128), NO_IMMEDIATE_WITHDRAWAL)
+            .unwrap();
+
+        let all_stakes = |deps: Deps, club_name: Option<&str>, staker: Option<&str>, min_amount: Option<u128>,
+                start_after: Option<(&str, &str)>, limit: Option<u32>| -> Vec<(String, String)> {
+            let stakes: StakesResponse = from_binary(&query(deps, mock_env(), QueryMsg::AllStakes {
+                club_name: club_name.map(String::from),
+                staker: staker.map(String::from),
+                min_amount: min_amount.map(Uint128::from),
+                start_after: start_after.map(|(club, staker)| (club.to_string(), staker.to_string())),
+                limit,
+            }).unwrap()).unwrap();
+            stakes.stakes.into_iter().map(|stake| (stake.club_name, stake.staker_address)).collect()
+        };
+        let key = |club: &str, staker: &str| (club.to_string(), staker.to_string());
+
+        assert_eq!(all_stakes(deps.as_ref(), None, None, None, None, Some(2)),
+            vec![key("CLUB001", "Staker0001"), key("CLUB001", "Staker0002")]);
+        assert_eq!(all_stakes(deps.as_ref(), None, None, None, Some(("CLUB001", "Staker0002")), Some(2)),
+            vec![key("CLUB001", "Staker0003"), key("CLUB002", "Staker0001")]);
+        assert_eq!(all_stakes(deps.as_ref(), None, Some("Staker0001"), None, None, None),
+            vec![key("CLUB001", "Staker0001"), key("CLUB002", "Staker0001")]);
+        assert_eq!(all_stakes(deps.as_ref(), None, Some("Staker0001"), None, Some(("CLUB001", "Staker0001")), None),
+            vec![key("CLUB002", "Staker0001")]);
+        assert_eq!(all_stakes(deps.as_ref(), Some("CLUB001"), None, Some(20), None, None),
+            vec![key("CLUB001", "Staker0002"), key("CLUB001", "Staker0003")]);
+        assert_eq!(all_stakes(deps.as_ref(), Some("CLUB002"), Some("Staker0001"), None, None, None),
+            vec![key("CLUB002", "Staker0001")]);
+
+        let all_bonds = |deps: Deps, club_name: Option<&str>, bonder: Option<&str>, start_after: Option<(&str, u64)>,
+                limit: Option<u32>| -> BondsResponse {
+            from_binary(&query(deps, mock_env(), QueryMsg::AllBonds {
+                club_name: club_name.map(|club| club.to_string()),
+                bonder: bonder.map(|bonder| bonder.to_string()),
+                min_amount: None,
+                start_after: start_after.map(|(club, bond_id)| (club.to_string(), bond_id)),
+                limit,
+            }).unwrap()).unwrap()
+        };
+        let bonds = all_bonds(deps.as_ref(), None, Some("Staker0001"), Some(("CLUB001", 0)), None);
+        assert_eq!(bonds.bonds.len(), 1);
+        assert_eq!(bonds.bonds[0].club_name, "CLUB002");
+        assert_eq!(bonds.last_key, Some(("CLUB002".to_string(), 0)));
+
+        // limit counts bonds, not clubs
+        let bond_key = |bonds: &BondsResponse| -> Vec<(String, String)> {
+            bonds.bonds.iter().map(|bond| (bond.club_name.clone(), bond.bonder_address.clone())).collect()
+        };
+        let page = all_bonds(deps.as_ref(), None, None, None, Some(1));
+        assert_eq!(bond_key(&page), vec![key("CLUB001", "Staker0001")]);
+        let last_key = page.last_key.unwrap();
+        let page = all_bonds(deps.as_ref(), None, None, Some((&last_key.0, last_key.1)), Some(1));
+        assert_eq!(bond_key(&page), vec![key("CLUB001", "Staker0003")]);
+        let last_key = page.last_key.unwrap();
+        let page = all_bonds(deps.as_ref(), None, None, Some((&last_key.0, last_key.1)), Some(1));
+        assert_eq!(bond_key(&page), vec![key("CLUB002", "Staker0001")]);
+        let last_key = page.last_key.unwrap();
+        let page = all_bonds(deps.as_ref(), None, None, Some((&last_key.0, last_key.1)), Some(1));
+        assert_eq!(page.bonds, vec![]);
+        assert_eq!(page.last_key, None);
+        let page = all_bonds(deps.as_ref(), Some("club001"), None, Some(("club001", 0)), None);
+        assert_eq!(bond_key(&page), vec![key("CLUB001", "Staker0003")]);
+
+        // Removing a bond does not move the bonds after it to another page
+        let page = all_bonds(deps.as_ref(), None, None, None, Some(1));
+        let last_key = page.last_key.unwrap();
+        let mut clubBonds = CLUB_BONDING_DETAILS.load(&deps.storage, "CLUB001".to_string()).unwrap();
+        clubBonds.remove(0);
+        CLUB_BONDING_DETAILS.save(&mut deps.storage, "CLUB001".to_string(), &clubBonds).unwrap();
+        let page = all_bonds(deps.as_ref(), None, None, Some((&last_key.0, last_key.1)), Some(1));
+        assert_eq!(bond_key(&page), vec![key("CLUB001", "Staker0003")]);
+        assert_eq!(page.last_key, Some(("CLUB001".to_string(), 1)));
+
+        let clubs: ClubsResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::AllClubs {
+            owner: Some("Owner002".to_string()),
+            start_after: None,
+            limit: None,
+        }).unwrap()).unwrap();
+        assert_eq!(clubs.clubs.len(), 1);
+        assert_eq!(clubs.clubs[0].club_name, "CLUB002");
+    }
+
+    #[test]
+    fn test_staker_portfolio() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
+
+        let owner1Info = mock_info("Owner001", &[]);
+        for club in ["CLUB001", "CLUB002"] {
+            buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), club.to_string(),
+                Uint128::from(CLUB_PRICE)).unwrap();
+        }
+        let mut cod = query_club_ownership_details(&deps.storage, "CLUB002".to_string()).unwrap();
+        cod.start_timestamp = mock_env().block.time.minus_seconds(22 * 24 * 60 * 60);
+        CLUB_OWNERSHIP_DETAILS.save(&mut deps.storage, "CLUB002".to_string(), &cod).unwrap();
+        release_club(deps.as_mut(), mock_env(), owner1Info.clone(), "Owner001".to_string(), "CLUB002".to_string())
+            .unwrap();
+        buy_a_club(deps.as_mut(), mock_env(), "Owner002".to_string(), "Owner001".to_string(), "CLUB002".to_string(),
+            Uint128::from(CLUB_PRICE)).unwrap();
+
+        stake_on_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "CLUB001".to_string(),
+            Uint128::from(50u128)).unwrap();
+        stake_on_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "CLUB002".to_string(),
+            Uint128::from(30u128)).unwrap();
+        stake_on_a_club(deps.as_mut(), mock_env(), "Staker0001".to_string(), "CLUB002".to_string(),
+            Uint128::from(70u128)).unwrap();
+        withdraw_stake_from_a_club(deps.as_mut(), mock_env(), owner1Info, "Owner001".to_string(),
+            "CLUB001".to_string(), Uint128::from(20u128), NO_IMMEDIATE_WITHDRAWAL).unwrap();
+
+        let portfolio: StakerPortfolioResponse = from_binary(&query(deps.as_ref(), mock_env(),
+            QueryMsg::StakerPortfolio { staker: "Owner001".to_string() }).unwrap()).unwrap();
+        assert_eq!(portfolio.stakes, vec![
+            PortfolioStake {
+                club_name: "CLUB001".to_string(),
+                staked_amount: Uint128::from(30u128),
+                pending_rewards: Uint128::zero(),
+            },
+            PortfolioStake {
+                club_name: "CLUB002".to_string(),
+                staked_amount: Uint128::from(30u128),
+                pending_rewards: Uint128::zero(),
+            },
+        ]);
+        assert_eq!(portfolio.bonds, vec![PortfolioBond {
+            club_name: "CLUB001".to_string(),
+            bonded_amount: Uint128::from(20u128),
+            matures_at: mock_env().block.time.plus_seconds(7 * 24 * 60 * 60),
+        }]);
+        assert_eq!(portfolio.owned_clubs, vec![PortfolioClub {
+            club_name: "CLUB001".to_string(),
+            reward_amount: Uint128::from(100u128),
+        }]);
+        assert_eq!(portfolio.previously_owned_clubs, vec![PortfolioClub {
+            club_name: "CLUB002".to_string(),
+            reward_amount: Uint128::from(100u128),
+        }]);
+        assert_eq!(portfolio.total_staked, Uint128::from(60u128));
+        assert_eq!(portfolio.total_bonded, Uint128::from(20u128));
+        assert_eq!(portfolio.total_owner_rewards, Uint128::from(200u128));
+
+        // A claimed bond leaves the portfolio
+        let mut maturedEnv = mock_env();
+        maturedEnv.block.time = maturedEnv.block.time.plus_seconds(8 * 24 * 60 * 60);
+        execute(deps.as_mut(), maturedEnv, mock_info("Owner001", &[]),
+            ExecuteMsg::ClaimUnbonded { club_name: None }).unwrap();
+        assert!(!BONDER_CLUBS.has(&deps.storage, ("Owner001".to_string(), "CLUB001".to_string())));
+        let portfolio = query_staker_portfolio(deps.as_ref(), "Owner001".to_string()).unwrap();
+        assert_eq!(portfolio.bonds, vec![]);
+        assert_eq!(portfolio.total_bonded, Uint128::zero());
+    }
+
+    #[test]
+    fn test_state_changes_emit_events() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
+        let timestamp = mock_env().block.time.seconds().to_string();
+
+        let res = buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(),
+            "CLUB001".to_string(), Uint128::from(CLUB_PRICE)).unwrap();
+        assert_eq!(res.events, vec![Event::new("club_bought")
+            .add_attribute("timestamp", timestamp.clone())
+            .add_attribute("club", "CLUB001")
+            .add_attribute("address", "Owner001")
+            .add_attribute("seller", "")
+            .add_attribute("amount", CLUB_PRICE.to_string())
+            .add_attribute("protocol_fee", (CLUB_PRICE / 20).to_string())]);
+
+        let res = stake_on_a_club(deps.as_mut(), mock_env(), "Staker0001".to_string(), "CLUB001".to_string(),
+            Uint128::from(100u128)).unwrap();
+        assert_eq!(res.attributes, vec![
+            attr("action", "stake_on_a_club"),
+            attr("club", "CLUB001"),
+            attr("address", "Staker0001"),
+            attr("amount", "100"),
+        ]);
+        assert_eq!(res.events[0].ty, "stake_added");
+
+        let res = withdraw_stake_from_a_club(deps.as_mut(), mock_env(), mock_info("Staker0001", &[]),
+            "Staker0001".to_string(), "CLUB001".to_string(), Uint128::from(40u128), NO_IMMEDIATE_WITHDRAWAL)
+            .unwrap();
+        let kinds: Vec<&str> = res.events.iter().map(|event| event.ty.as_str()).collect();
+        assert_eq!(kinds, vec!["stake_withdrawn", "bond_created"]);
+        assert_eq!(res.events[1].attributes, vec![
+            attr("timestamp", timestamp),
+            attr("club", "CLUB001"),
+            attr("address", "Staker0001"),
+            attr("amount", "40"),
+            attr("matures_at", mock_env().block.time.plus_seconds(7 * 24 * 60 * 60).seconds().to_string()),
+        ]);
+    }
+
+    #[test]
+    fn test_staking_through_cw20_receive() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
+
+        let tokenInfo = mock_info("cwtoken11111", &[]);
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            tokenInfo.clone(),
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: "Owner001".to_string(),
+                amount: Uint128::from(CLUB_PRICE),
+                msg: to_binary(&ReceiveMsg::BuyAClub {
+                    seller: "".to_string(),
+                    club_name: "CLUB001".to_string(),
+                })
+                .unwrap(),
+            }),
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            tokenInfo.clone(),
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: "Staker0001".to_string(),
+                amount: Uint128::from(33u128),
+                msg: to_binary(&ReceiveMsg::StakeOnAClub {
+                    club_name: "CLUB001".to_string(),
+                })
+                .unwrap(),
+            }),
+        )
+        .unwrap();
+
+        // Any other sender claiming to forward tokens must be rejected
+        let fakeTokenInfo = mock_info("Staker0001", &[]);
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            fakeTokenInfo,
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: "Staker0001".to_string(),
+                amount: Uint128::from(1000000u128),
+                msg: to_binary(&ReceiveMsg::StakeOnAClub {
+                    club_name: "CLUB001".to_string(),
+                })
+                .unwrap(),
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        let all_stakes = query_all_stakes(&mut deps.storage).unwrap();
+        assert_eq!(all_stakes.len(), 1);
+        assert_eq!(all_stakes[0].staker_address, "Staker0001".to_string());
+        assert_eq!(all_stakes[0].staked_amount, Uint128::from(33u128));
+
+        let cod = query_club_ownership_details(&mut deps.storage, "CLUB001".to_string()).unwrap();
+        assert_eq!(cod.owner_address, "Owner001".to_string());
+        assert_eq!(cod.price_paid, Uint128::from(CLUB_PRICE));
+    }
+
+    #[test]
+    fn test_club_names_are_normalized_and_validated() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
+
+        buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), " club001 ".to_string(), Uint128::from(CLUB_PRICE)).unwrap();
+        stake_on_a_club(deps.as_mut(), mock_env(), "Staker001".to_string(), "Club001".to_string(), Uint128::from(33u128)).unwrap();
+
+        let cod = query_club_ownership_details(&mut deps.storage, "CLUB001".to_string()).unwrap();
+        assert_eq!(cod.owner_address, "Owner001".to_string());
+        let stakes: StakesResponse = from_binary(
+            &query(deps.as_ref(), mock_env(), QueryMsg::ClubStakingDetails { club_name: "club001".to_string() }).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(stakes.stakes.len(), 1);
+        assert_eq!(stakes.stakes[0].club_name, "CLUB001".to_string());
+
+        let err = stake_on_a_club(deps.as_mut(), mock_env(), "Staker001".to_string(), " AB ".to_string(), Uint128::from(33u128)).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidClubName {
+                club: " AB ".to_string(),
+                reason: "must be between 3 and 32 characters long".to_string(),
+            }
+        );
+        let err = stake_on_a_club(deps.as_mut(), mock_env(), "Staker001".to_string(), "CLUB@1".to_string(), Uint128::from(33u128)).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidClubName {
+                club: "CLUB@1".to_string(),
+                reason: "may only contain letters, digits, spaces, '-' and '_', found '@'".to_string(),
+            }
+        );
+        let err = query(deps.as_ref(), mock_env(), QueryMsg::Club { club_name: "x".repeat(33) }).unwrap_err();
+        assert!(err.to_string().contains("must be between 3 and 32 characters long"));
+    }
+
+    #[test]
+    fn test_club_registry_and_lifecycle() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
+
+        let tokenInfo = mock_info("cwtoken11111", &[]);
+        let create_msg = |amount: u128| {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: "Creator001".to_string(),
+                amount: Uint128::from(amount),
+                msg: to_binary(&ReceiveMsg::CreateClub {
+                    club_name: "CLUB005".to_string(),
+                    display_name: "Club Five".to_string(),
+                    description: Some("The fifth club".to_string()),
+                    logo: None,
+                })
+                .unwrap(),
+            })
+        };
+
+        let err = execute(deps.as_mut(), mock_env(), tokenInfo.clone(), create_msg(499)).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InsufficientFunds {
+                required: Uint128::from(500u128),
+                sent: Uint128::from(499u128),
+            }
+        );
+
+        // The fee goes to the treasury and the excess back to the creator
+        let res = execute(deps.as_mut(), mock_env(), tokenInfo.clone(), create_msg(600)).unwrap();
+        assert_eq!(res.messages.len(), 2);
+        let club: ClubResponse = from_binary(
+            &query(deps.as_ref(), mock_env(), QueryMsg::Club { club_name: "CLUB005".to_string() }).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(club.display_name, "Club Five".to_string());
+        assert_eq!(club.status, ClubStatus::Active);
+        assert_eq!(club.creator, "Creator001".to_string());
+
+        let err = execute(deps.as_mut(), mock_env(), tokenInfo.clone(), create_msg(500)).unwrap_err();
+        assert_eq!(err, ContractError::ClubAlreadyExists { club: "CLUB005".to_string() });
+
+        // Unregistered clubs cannot be bought
+        let err = buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), "CLUB999".to_string(), Uint128::from(CLUB_PRICE)).unwrap_err();
+        assert_eq!(err, ContractError::ClubNotFound { club: "CLUB999".to_string() });
+
+        let set_status = |status: ClubStatus| ExecuteMsg::SetClubStatus { club_name: "CLUB005".to_string(), status };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("Creator001", &[]), set_status(ClubStatus::Paused)).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        let adminInfo = mock_info("admin11111", &[]);
+        execute(deps.as_mut(), mock_env(), adminInfo.clone(), set_status(ClubStatus::Paused)).unwrap();
+        let err = stake_on_a_club(deps.as_mut(), mock_env(), "Staker001".to_string(), "CLUB005".to_string(), Uint128::from(33u128)).unwrap_err();
+        assert_eq!(err, ContractError::ClubNotActive { club: "CLUB005".to_string(), status: ClubStatus::Paused });
+
+        execute(deps.as_mut(), mock_env(), adminInfo.clone(), set_status(ClubStatus::Active)).unwrap();
+        buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), "CLUB005".to_string(), Uint128::from(CLUB_PRICE)).unwrap();
+        stake_on_a_club(deps.as_mut(), mock_env(), "Staker001".to_string(), "CLUB005".to_string(), Uint128::from(33u128)).unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            adminInfo.clone(),
+            ExecuteMsg::UpdateClubMetadata {
+                club_name: "CLUB005".to_string(),
+                display_name: None,
+                description: None,
+                logo: Some(Logo::Embedded(EmbeddedLogo::Png(Binary::from(vec![0u8; 6 * 1024])))),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::LogoTooBig {});
+
+        let update_description = |description: Option<Option<String>>| ExecuteMsg::UpdateClubMetadata {
+            club_name: "CLUB005".to_string(),
+            display_name: None,
+            description,
+            logo: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), adminInfo.clone(), update_description(Some(Some("x".repeat(1025))))).unwrap_err();
+        assert_eq!(err, ContractError::DescriptionTooLong {});
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            adminInfo.clone(),
+            ExecuteMsg::UpdateClubMetadata {
+                club_name: "CLUB005".to_string(),
+                display_name: Some("x".repeat(65)),
+                description: None,
+                logo: None,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::DisplayNameTooLong {});
+
+        // A missing description keeps it, an explicit null clears it
+        let keepMsg: ExecuteMsg = from_slice(br#"{"update_club_metadata":{"club_name":"CLUB005"}}"#).unwrap();
+        assert_eq!(keepMsg, update_description(None));
+        execute(deps.as_mut(), mock_env(), adminInfo.clone(), keepMsg).unwrap();
+        assert_eq!(CLUBS.load(&deps.storage, "CLUB005".to_string()).unwrap().description, Some("The fifth club".to_string()));
+        let clearMsg: ExecuteMsg = from_slice(br#"{"update_club_metadata":{"club_name":"CLUB005","description":null}}"#).unwrap();
+        assert_eq!(clearMsg, update_description(Some(None)));
+        execute(deps.as_mut(), mock_env(), adminInfo.clone(), clearMsg).unwrap();
+        assert_eq!(CLUBS.load(&deps.storage, "CLUB005".to_string()).unwrap().description, None);
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            adminInfo.clone(),
+            ExecuteMsg::CreateClub {
+                club_name: "CLUB006".to_string(),
+                display_name: "x".repeat(65),
+                description: None,
+                logo: None,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::DisplayNameTooLong {});
+
+        execute(deps.as_mut(), mock_env(), adminInfo.clone(), set_status(ClubStatus::Retired)).unwrap();
+        for status in [ClubStatus::Active, ClubStatus::Paused] {
+            let err = execute(deps.as_mut(), mock_env(), adminInfo.clone(), set_status(status)).unwrap_err();
+            assert_eq!(err, ContractError::ClubRetired { club: "CLUB005".to_string() });
+        }
+        let registry: ClubRegistryResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::ClubRegistry { status: Some(ClubStatus::Active), start_after: None, limit: None },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let club_names: Vec<String> = registry.clubs.into_iter().map(|club| club.club_name).collect();
+        assert_eq!(club_names, vec!["CLUB001", "CLUB002", "CLUB003", "CLUB004"]);
+    }
+
+    #[test]
+    fn test_buying_pays_seller_treasury_and_refunds_excess() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
+
+        let err = buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
+            Uint128::from(CLUB_PRICE - 1)).unwrap_err();
+        assert_eq!(err, ContractError::InsufficientFunds {
+            required: Uint128::from(CLUB_PRICE),
+            sent: Uint128::from(CLUB_PRICE - 1),
+        });
+
+        // First sale goes entirely to the treasury
+        let res = buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
+            Uint128::from(CLUB_PRICE)).unwrap();
+        assert_eq!(res.messages, vec![
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: "cwtoken11111".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "treasury11111".to_string(),
+                    amount: Uint128::from(CLUB_PRICE),
+                }).unwrap(),
+                funds: vec![],
+            }),
+        ]);
+
+        let mut cod = query_club_ownership_details(&mut deps.storage, "CLUB001".to_string()).unwrap();
+        cod.owner_released = true;
+        CLUB_OWNERSHIP_DETAILS.save(&mut deps.storage, "CLUB001".to_string(), &cod).unwrap();
+
+        // Resale: 5% fee to the treasury, the rest to the seller and the excess back to the buyer
+        let res = buy_a_club(deps.as_mut(), mock_env(), "Owner002".to_string(), "Owner001".to_string(), "CLUB001".to_string(),
+            Uint128::from(CLUB_PRICE + 7)).unwrap();
+        let fee = CLUB_PRICE * 5 / 100;
+        let expected: Vec<SubMsg> = vec![
+            ("treasury11111", fee),
+            ("Owner001", CLUB_PRICE - fee),
+            ("Owner002", 7u128),
+        ].into_iter().map(|(recipient, amount)| {
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: "cwtoken11111".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: recipient.to_string(),
+                    amount: Uint128::from(amount),
+                }).unwrap(),
+                funds: vec![],
+            })
+        }).collect();
+        assert_eq!(res.messages, expected);
+
+        let cod = query_club_ownership_details(&mut deps.storage, "CLUB001".to_string()).unwrap();
+        assert_eq!(cod.owner_address, "Owner002".to_string());
+        assert_eq!(cod.price_paid, Uint128::from(CLUB_PRICE));
+    }
+
+    #[test]
+    fn test_claim_rewards_transfers_tokens() {
+        // The 99 staked below plus 40 tokens of rewards
+        let mut deps = mock_dependencies_with_token_balance(Uint128::from(139u128));
+        instantiate_contract(deps.as_mut());
+
+        buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
+            Uint128::from(CLUB_PRICE)).unwrap();
+        stake_on_a_club(deps.as_mut(), mock_env(), "Staker0001".to_string(), "CLUB001".to_string(),
+            Uint128::from(99u128)).unwrap();
+        let mut stakes = query_club_staking_details(&mut deps.storage, "CLUB001".to_string()).unwrap();
+        stakes[0].reward_amount = Uint128::from(80u128);
+        club_staking_details().save(&mut deps.storage, ("CLUB001".to_string(), "Staker0001".to_string()), &stakes[0]).unwrap();
+
+        let stakerInfo = mock_info("Staker0001", &[]);
+        let res = claim_rewards(deps.as_mut(), mock_env(), stakerInfo.clone(), "Staker0001".to_string(),
+            "CLUB001".to_string(), Uint128::from(30u128)).unwrap();
+        assert_eq!(res.messages, vec![SubMsg::new(WasmMsg::Execute {
+            contract_addr: "cwtoken11111".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "Staker0001".to_string(),
+                amount: Uint128::from(30u128),
+            }).unwrap(),
+            funds: vec![],
+        })]);
+        assert_eq!(res.attributes, vec![
+            attr("action", "claim_rewards"),
+            attr("club", "CLUB001"),
+            attr("address", "Staker0001"),
+            attr("amount", "30"),
+        ]);
+
+        // 50 rewards are left but the contract only holds 40 tokens besides the stake
+        let err = claim_rewards(deps.as_mut(), mock_env(), stakerInfo, "Staker0001".to_string(),
+            "CLUB001".to_string(), Uint128::from(45u128)).unwrap_err();
+        assert_eq!(err, ContractError::InsufficientContractBalance {
+            required: Uint128::from(45u128),
+            available: Uint128::from(40u128),
+        });
+    }
+
+    #[test]
+    fn test_claims_fail_without_something_to_claim() {
+        let mut deps = mock_dependencies_with_token_balance(Uint128::from(100u128));
+        instantiate_contract(deps.as_mut());
+        buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
+            Uint128::from(CLUB_PRICE)).unwrap();
+        let noRewards = |requested: u128| ContractError::InsufficientRewards {
+            requested: Uint128::from(requested),
+            available: Uint128::zero(),
+        };
+
+        let err = claim_rewards(deps.as_mut(), mock_env(), mock_info("Staker0001", &[]), "Staker0001".to_string(),
+            "CLUB001".to_string(), Uint128::from(5u128)).unwrap_err();
+        assert_eq!(err, noRewards(5));
+        let err = claim_rewards(deps.as_mut(), mock_env(), mock_info("Staker0001", &[]), "Staker0001".to_string(),
+            "CLUB001".to_string(), Uint128::zero()).unwrap_err();
+        assert_eq!(err, ContractError::InvalidZeroAmount {});
+
+        let err = claim_owner_rewards(deps.as_mut(), mock_env(), mock_info("Owner002", &[]), "Owner002".to_string(),
+            "CLUB001".to_string(), Uint128::from(5u128)).unwrap_err();
+        assert_eq!(err, ContractError::NotClubOwner { club: "CLUB001".to_string(), address: "Owner002".to_string() });
+        let err = claim_owner_rewards(deps.as_mut(), mock_env(), mock_info("Owner001", &[]), "Owner001".to_string(),
+            "CLUB002".to_string(), Uint128::from(5u128)).unwrap_err();
+        assert_eq!(err, ContractError::ClubNotFound { club: "CLUB002".to_string() });
+        let err = claim_owner_rewards(deps.as_mut(), mock_env(), mock_info("Owner001", &[]), "Owner001".to_string(),
+            "CLUB001".to_string(), Uint128::zero()).unwrap_err();
+        assert_eq!(err, ContractError::InvalidZeroAmount {});
+
+        // Claiming as a previous owner never stores a record for the claimant
+        let err = claim_previous_owner_rewards(deps.as_mut(), mock_env(), mock_info("Owner002", &[]),
+            "Owner002".to_string(), "CLUB001".to_string(), Uint128::from(5u128)).unwrap_err();
+        assert_eq!(err, noRewards(5));
+        let err = claim_previous_owner_rewards(deps.as_mut(), mock_env(), mock_info("Owner002", &[]),
+            "Owner002".to_string(), "CLUB001".to_string(), Uint128::zero()).unwrap_err();
+        assert_eq!(err, ContractError::InvalidZeroAmount {});
+        assert!(!CLUB_PREVIOUS_OWNER_DETAILS.has(&deps.storage, ("CLUB001".to_string(), "Owner002".to_string())));
+    }
+
+    #[test]
+    fn test_rewards_are_not_paid_out_of_escrowed_funds() {
+        // The contract holds the staked principal and the offer, nothing else
+        let mut deps = mock_dependencies_with_token_balance(Uint128::from(99u128 + CLUB_PRICE));
+        instantiate_contract(deps.as_mut());
+
+        buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
+            Uint128::from(CLUB_PRICE)).unwrap();
+        stake_on_a_club(deps.as_mut(), mock_env(), "Staker0001".to_string(), "CLUB001".to_string(),
+            Uint128::from(99u128)).unwrap();
+        make_offer(deps.as_mut(), mock_env(), "Bidder001".to_string(), "CLUB001".to_string(),
+            Uint128::from(CLUB_PRICE), None).unwrap();
+        assert_eq!(ESCROWED_FUNDS.load(&deps.storage).unwrap(), Uint128::from(99u128 + CLUB_PRICE));
+
+        let mut stakes = query_club_staking_details(&mut deps.storage, "CLUB001".to_string()).unwrap();
+        stakes[0].reward_amount = Uint128::from(80u128);
+        club_staking_details().save(&mut deps.storage, ("CLUB001".to_string(), "Staker0001".to_string()), &stakes[0]).unwrap();
+        let err = claim_rewards(deps.as_mut(), mock_env(), mock_info("Staker0001", &[]), "Staker0001".to_string(),
+            "CLUB001".to_string(), Uint128::from(1u128)).unwrap_err();
+        assert_eq!(err, ContractError::InsufficientContractBalance {
+            required: Uint128::from(1u128),
+            available: Uint128::zero(),
+        });
+
+        // Funds leaving the contract are released again
+        execute(deps.as_mut(), mock_env(), mock_info("Bidder001", &[]),
+            ExecuteMsg::WithdrawOffer { club_name: "CLUB001".to_string() }).unwrap();
+        withdraw_stake_from_a_club(deps.as_mut(), mock_env(), mock_info("Staker0001", &[]), "Staker0001".to_string(),
+            "CLUB001".to_string(), Uint128::from(99u128), IMMEDIATE_WITHDRAWAL).unwrap();
+        assert_eq!(ESCROWED_FUNDS.load(&deps.storage).unwrap(), Uint128::zero());
+    }
+
+    #[test]
+    fn test_claim_unbonded_refunds_only_own_matured_bonds() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
+
+        buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
+            Uint128::from(CLUB_PRICE)).unwrap();
+        stake_on_a_club(deps.as_mut(), mock_env(), "Staker0001".to_string(), "CLUB001".to_string(),
+            Uint128::from(99u128)).unwrap();
+        stake_on_a_club(deps.as_mut(), mock_env(), "Staker0002".to_string(), "CLUB001".to_string(),
+            Uint128::from(50u128)).unwrap();
+
+        let staker1Info = mock_info("Staker0001", &[]);
+        let staker2Info = mock_info("Staker0002", &[]);
+        withdraw_stake_from_a_club(deps.as_mut(), mock_env(), staker1Info.clone(), "Staker0001".to_string(),
+            "CLUB001".to_string(), Uint128::from(11u128), NO_IMMEDIATE_WITHDRAWAL).unwrap();
+        withdraw_stake_from_a_club(deps.as_mut(), mock_env(), staker1Info.clone(), "Staker0001".to_string(),
+            "CLUB001".to_string(), Uint128::from(12u128), NO_IMMEDIATE_WITHDRAWAL).unwrap();
+        withdraw_stake_from_a_club(deps.as_mut(), mock_env(), staker2Info.clone(), "Staker0002".to_string(),
+            "CLUB001".to_string(), Uint128::from(20u128), NO_IMMEDIATE_WITHDRAWAL).unwrap();
+
+        // Nothing has matured yet
+        let err = execute(deps.as_mut(), mock_env(), staker1Info.clone(),
+            ExecuteMsg::ClaimUnbonded { club_name: None }).unwrap_err();
+        assert_eq!(err, ContractError::NoMaturedBonds {});
+
+        // Age the 11 bond of Staker0001 and the bond of Staker0002 past the bonding period
+        let now = mock_env().block.time;
+        let mut bonds = query_club_bonding_details(&mut deps.storage, "CLUB001".to_string()).unwrap();
+        for bond in bonds.iter_mut() {
+            if bond.bonded_amount != Uint128::from(12u128) {
+                bond.bonding_start_timestamp = now.minus_seconds(8 * 24 * 60 * 60);
+            }
+        }
+        CLUB_BONDING_DETAILS.save(&mut deps.storage, "CLUB001".to_string(), &bonds).unwrap();
+
+        let res = execute(deps.as_mut(), mock_env(), staker1Info.clone(),
+            ExecuteMsg::ClaimUnbonded { club_name: Some("CLUB001".to_string()) }).unwrap();
+        assert_eq!(res.messages, vec![SubMsg::new(WasmMsg::Execute {
+            contract_addr: "cwtoken11111".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "Staker0001".to_string(),
+                amount: Uint128::from(11u128),
+            }).unwrap(),
+            funds: vec![],
+        })]);
+
+        let remaining: Vec<Uint128> = query_all_bonds(deps.as_ref(), None, None, None, None, None).unwrap().bonds
+            .into_iter().map(|bond| bond.bonded_amount).collect();
+        assert_eq!(remaining, vec![Uint128::from(12u128), Uint128::from(20u128)]);
+        let wallet = CONTRACT_WALLET.load(&deps.storage, &Addr::unchecked("Staker0001")).unwrap();
+        assert_eq!(wallet, Uint128::from(88u128));
+    }
+
+    #[test]
+    fn test_immediate_withdrawal_penalty_is_burned_or_pooled() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
+
+        buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
+            Uint128::from(CLUB_PRICE)).unwrap();
+        stake_on_a_club(deps.as_mut(), mock_env(), "Staker0001".to_string(), "CLUB001".to_string(),
+            Uint128::from(200u128)).unwrap();
+
+        let stakerInfo = mock_info("Staker0001", &[]);
+        let err = withdraw_stake_from_a_club(deps.as_mut(), mock_env(), stakerInfo.clone(), "Staker0001".to_string(),
+            "CLUB001".to_string(), Uint128::from(201u128), IMMEDIATE_WITHDRAWAL).unwrap_err();
+        assert_eq!(err, ContractError::InsufficientStake {
+            requested: Uint128::from(201u128),
+            staked: Uint128::from(200u128),
+        });
+
+        let res = withdraw_stake_from_a_club(deps.as_mut(), mock_env(), stakerInfo.clone(), "Staker0001".to_string(),
+            "CLUB001".to_string(), Uint128::from(100u128), IMMEDIATE_WITHDRAWAL).unwrap();
+        assert_eq!(res.messages, vec![
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: "cwtoken11111".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "Staker0001".to_string(),
+                    amount: Uint128::from(90u128),
+                }).unwrap(),
+                funds: vec![],
+            }),
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: "cwtoken11111".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Burn {
+                    amount: Uint128::from(10u128),
+                }).unwrap(),
+                funds: vec![],
+            }),
+        ]);
+
+        let mut config = CONFIG.load(&deps.storage).unwrap();
+        config.penalty_destination = PenaltyDestination::RewardPool;
+        CONFIG.save(&mut deps.storage, &config).unwrap();
+
+        let res = withdraw_stake_from_a_club(deps.as_mut(), mock_env(), stakerInfo.clone(), "Staker0001".to_string(),
+            "CLUB001".to_string(), Uint128::from(50u128), IMMEDIATE_WITHDRAWAL).unwrap();
+        assert_eq!(res.messages.len(), 1);
+        let reward = query_reward_amount(deps.as_ref()).unwrap();
+        assert_eq!(reward.amount, Uint128::zero());
+        assert_eq!(reward.penalty_pool, Uint128::from(5u128));
+
+        // The pool goes out with the next distribution only
+        let adminInfo = mock_info("admin11111", &[]);
+        execute(deps.as_mut(), mock_env(), adminInfo.clone(),
+            ExecuteMsg::SetRewardAmount { amount: Uint128::from(100u128) }).unwrap();
+        let distributed = |res: Response| res.events[0].attributes.iter()
+            .find(|attr| attr.key == "amount").unwrap().value.clone();
+        let res = execute(deps.as_mut(), mock_env(), adminInfo.clone(),
+            ExecuteMsg::CalculateAndDistributeRewards {}).unwrap();
+        assert_eq!(distributed(res), "104");
+        let reward = query_reward_amount(deps.as_ref()).unwrap();
+        assert_eq!(reward.amount, Uint128::from(100u128));
+        assert_eq!(reward.penalty_pool, Uint128::zero());
+        let res = execute(deps.as_mut(), mock_env(), adminInfo,
+            ExecuteMsg::CalculateAndDistributeRewards {}).unwrap();
+        assert_eq!(distributed(res), "100");
+
+        // Amounts whose product with the percentage overflows still pay the penalty
+        let bigStake = Uint128::from(u128::MAX / 10);
+        stake_on_a_club(deps.as_mut(), mock_env(), "Staker0002".to_string(), "CLUB001".to_string(),
+            bigStake).unwrap();
+        withdraw_stake_from_a_club(deps.as_mut(), mock_env(), mock_info("Staker0002", &[]), "Staker0002".to_string(),
+            "CLUB001".to_string(), bigStake, IMMEDIATE_WITHDRAWAL).unwrap();
+        let reward = query_reward_amount(deps.as_ref()).unwrap();
+        assert_eq!(reward.penalty_pool, bigStake.multiply_ratio(10u128, 100u128));
+    }
+
+    #[test]
+    fn test_update_config() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
+
+        let update_msg = |owner: u128, winner: u128, all: u128| ExecuteMsg::UpdateConfig {
+            treasury_address: None,
+            protocol_fee_percentage: None,
+            early_withdrawal_penalty_percentage: None,
+            penalty_destination: None,
+            club_price: Some(Uint128::from(5000u128)),
+            club_buying_reward_amount: None,
+            club_staking_reward_amount: None,
+            club_locking_duration: None,
+            club_bonding_duration: Some(3 * 24 * 60 * 60),
+            owner_reward_percentage: Some(Uint128::from(owner)),
+            winner_club_reward_percentage: Some(Uint128::from(winner)),
+            all_stakers_reward_percentage: Some(Uint128::from(all)),
+            club_creation_fee: None,
+            auction_extension_duration: None,
+            pricing_model: None,
+            max_clubs_per_owner: None,
+        };
+
+        let err = execute(deps.as_mut(), mock_env(), mock_info("Owner001", &[]), update_msg(2, 18, 80)).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        let adminInfo = mock_info("admin11111", &[]);
+        let err = execute(deps.as_mut(), mock_env(), adminInfo.clone(), update_msg(2, 18, 81)).unwrap_err();
+        assert_eq!(err, ContractError::InvalidRewardSplit { total: Uint128::from(101u128) });
+
+        execute(deps.as_mut(), mock_env(), adminInfo, update_msg(2, 18, 80)).unwrap();
+        let config: ConfigResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+        assert_eq!(config.club_price, Uint128::from(5000u128));
+        assert_eq!(config.club_bonding_duration, 3 * 24 * 60 * 60);
+        assert_eq!(config.club_locking_duration, 21 * 24 * 60 * 60);
+        assert_eq!(config.owner_reward_percentage, Uint128::from(2u128));
+
+        buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), "CLUB001".to_string(),
+            Uint128::from(5000u128)).unwrap();
+        let cod = query_club_ownership_details(&mut deps.storage, "CLUB001".to_string()).unwrap();
+        assert_eq!(cod.price_paid, Uint128::from(5000u128));
+    }
+
+    #[test]
+    fn test_refund_sweep_pages_clubs_and_skips_uncovered_bonds() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
+        for club_name in ["CLUB001", "CLUB002"] {
+            buy_a_club(deps.as_mut(), mock_env(), "Owner001".to_string(), "".to_string(), club_name.to_string(),
+                Uint128::from(CLUB_PRICE)).unwrap();
+            stake_on_a_club(deps.as_mut(), mock_env(), "Staker0001".to_string(), club_name.to_string(),
+                Uint128::from(20u128)).unwrap();
+            withdraw_stake_from_a_club(deps.as_mut(), mock_env(), mock_info("Staker0001", &[]), "Staker0001".to_string(),
+                club_name.to_string(), Uint128::from(20u128), NO_IMMEDIATE_WITHDRAWAL).unwrap();
+        }
+        stake_on_a_club(deps.as_mut(), mock_env(), "Staker0002".to_string(), "CLUB001".to_string(),
+            Uint128::from(5u128)).unwrap();
+        withdraw_stake_from_a_club(deps.as_mut(), mock_env(), mock_info("Staker0002", &[]), "Staker0002".to_string(),
+            "CLUB001".to_string(), Uint128::from(5u128), NO_IMMEDIATE_WITHDRAWAL).unwrap();
+        // A wallet that no longer covers the bond
+        CONTRACT_WALLET.save(&mut deps.storage, &Addr::unchecked("Staker0002"), &Uint128::zero()).unwrap();
+
+        let mut maturedEnv = mock_env();
+        maturedEnv.block.time = maturedEnv.block.time.plus_seconds(8 * 24 * 60 * 60);
+        let adminInfo = mock_info("admin11111", &[]);
+        let attribute = |res: &Response, key: &str| res.attributes.iter()
+            .find(|attr| attr.key == key).map(|attr| attr.value.clone());
+
+        let res = periodically_refund_stakeouts(deps.as_mut(), maturedEnv.clone(), adminInfo.clone(), None, Some(1)).unwrap();
+        assert_eq!(attribute(&res, "bonds_settled"), Some("1".to_string()));
+        assert_eq!(attribute(&res, "bonds_skipped"), Some("1".to_string()));
+        assert_eq!(attribute(&res, "last_club"), Some("CLUB001".to_string()));
+        let bonds = query_club_bonding_details(&deps.storage, "CLUB001".to_string()).unwrap();
+        assert_eq!(bonds.len(), 1);
+        assert_eq!(bonds[0].bonder_address, "Staker0002".to_string());
+        assert_eq!(query_club_bonding_details(&deps.storage, "CLUB002".to_string()).unwrap().len(), 1);
+
+        let res = periodically_refund_stakeouts(deps.as_mut(), maturedEnv, adminInfo, Some("club001".to_string()), None).unwrap();
+        assert_eq!(attribute(&res, "bonds_settled"), Some("1".to_string()));
+        assert_eq!(attribute(&res, "total_refunded"), Some("20".to_string()));
+        assert_eq!(attribute(&res, "last_club"), None);
+        assert_eq!(query_club_bonding_details(&deps.storage, "CLUB002".to_string()).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_grant_and_revoke_roles() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
+
+        let keeperInfo = mock_info("keeper0001", &[]);
+        let err = periodically_refund_stakeouts(deps.as_mut(), mock_env(), keeperInfo.clone(), None, None).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        let grant = ExecuteMsg::GrantRole {
+            address: "keeper0001".to_string(),
+            role: Role::RefundKeeper,
+        };
+        let err = execute(deps.as_mut(), mock_env(), keeperInfo.clone(), grant.clone()).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        let adminInfo = mock_info("admin11111", &[]);
+        execute(deps.as_mut(), mock_env(), adminInfo.clone(), grant).unwrap();
+        periodically_refund_stakeouts(deps.as_mut(), mock_env(), keeperInfo.clone(), None, None).unwrap();
+
+        // A refund keeper is not a reward distributor
+        let err = set_reward_amount(deps.as_mut(), mock_env(), keeperInfo.clone(), Uint128::from(10u128)).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        let roles: RolesResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Roles {}).unwrap()).unwrap();
+        assert_eq!(roles.holders, vec![RoleHolder {
+            address: "keeper0001".to_string(),
+            roles: vec![Role::RefundKeeper],
+        }]);
+
+        execute(deps.as_mut(), mock_env(), adminInfo, ExecuteMsg::RevokeRole {
+            address: "keeper0001".to_string(),
+            role: Role::RefundKeeper,
+        }).unwrap();
+        let err = periodically_refund_stakeouts(deps.as_mut(), mock_env(), keeperInfo, None, None).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        let roles: RolesResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Roles {}).unwrap()).unwrap();
+        assert_eq!(roles.holders.len(), 0);
+    }
+
+    #[test]
+    fn test_two_step_admin_transfer() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
+
+        let adminInfo = mock_info("admin11111", &[]);
+        let newAdminInfo = mock_info("admin22222", &[]);
+        let propose = ExecuteMsg::ProposeNewAdmin {
+            address: "admin22222".to_string(),
+            expiry: Some(Expiration::AtHeight(mock_env().block.height + 100)),
+        };
+
+        let err = execute(deps.as_mut(), mock_env(), newAdminInfo.clone(), propose.clone()).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        execute(deps.as_mut(), mock_env(), adminInfo.clone(), propose.clone()).unwrap();
+
+        let pending: PendingAdminResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::PendingAdmin {}).unwrap()).unwrap();
+        assert_eq!(pending.pending_admin.unwrap().address, Addr::unchecked("admin22222"));
+
+        // Only the proposed address can accept and only before the expiry
+        let err = execute(deps.as_mut(), mock_env(), mock_info("Owner001", &[]), ExecuteMsg::AcceptAdmin {}).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        let mut late_env = mock_env();
+        late_env.block.height += 100;
+        let err = execute(deps.as_mut(), late_env, newAdminInfo.clone(), ExecuteMsg::AcceptAdmin {}).unwrap_err();
+        assert_eq!(err, ContractError::Expired {});
+
+        // A cancelled proposal cannot be accepted
+        execute(deps.as_mut(), mock_env(), adminInfo.clone(), ExecuteMsg::CancelAdminProposal {}).unwrap();
+        execute(deps.as_mut(), mock_env(), newAdminInfo.clone(), ExecuteMsg::AcceptAdmin {}).unwrap_err();
+
+        execute(deps.as_mut(), mock_env(), adminInfo.clone(), propose).unwrap();
+        execute(deps.as_mut(), mock_env(), newAdminInfo.clone(), ExecuteMsg::AcceptAdmin {}).unwrap();
+        let config = CONFIG.load(&deps.storage).unwrap();
+        assert_eq!(config.admin_address, Addr::unchecked("admin22222"));
+        assert_eq!(PENDING_ADMIN.may_load(&deps.storage).unwrap(), None);
+
+        let err = execute(deps.as_mut(), mock_env(), adminInfo, ExecuteMsg::CancelAdminProposal {}).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+    }
+
+    #[test]
+    fn test_migrate_drops_obsolete_staking_fields() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
+        set_contract_version(&mut deps.storage, CONTRACT_NAME, "0.9.1").unwrap();
+        deps.storage.set(CONFIG_KEY.as_bytes(),
+            br#"{"cw20_token_address":"cwtoken11111","admin_address":"admin11111"}"#);
+
+        let legacy_stake = crate::state::LegacyClubStakingDetails {
+            club_name: "CLUB001".to_string(),
+            staker_address: "Staker0001".to_string(),
+            staking_start_timestamp: mock_env().block.time,
+            staked_amount: Uint128::from(33u128),
+            staking_duration: 0,
+            reward_amount: Uint128::from(7u128),
+        };
+        LEGACY_CLUB_STAKING_DETAILS.save(&mut deps.storage, "CLUB001".to_string(), &vec![legacy_stake]).unwrap();
+
+        migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap();
+
+        assert_eq!(get_contract_version(&deps.storage).unwrap().version, CONTRACT_VERSION.to_string());
+        let stakes = query_club_staking_details(&mut deps.storage, "CLUB001".to_string()).unwrap();
+        assert_eq!(stakes, vec![ClubStakingDetails {
+            club_name: "CLUB001".to_string(),
+            staker_address: "Staker0001".to_string(),
+            staked_amount: Uint128::from(33u128),
+            reward_amount: Uint128::from(7u128),
+            ..Default::default()
+        }]);
+        let raw = LEGACY_CLUB_STAKING_DETAILS.load(&deps.storage, "CLUB001".to_string());
+        assert!(raw.is_err());
+    }
+
+    #[test]
+    fn test_migrate_upgrades_legacy_config() {
+        let mut deps = mock_dependencies(&[]);
+        set_contract_version(&mut deps.storage, CONTRACT_NAME, "0.9.1").unwrap();
+        deps.storage.set(CONFIG_KEY.as_bytes(),
+            br#"{"cw20_token_address":"cwtoken11111","admin_address":"admin11111"}"#);
+        assert!(CONFIG.load(&deps.storage).is_err());
+
+        migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap();
+
+        let config = CONFIG.load(&deps.storage).unwrap();
+        assert_eq!(config.cw20_token_address, Addr::unchecked("cwtoken11111"));
+        assert_eq!(config.admin_address, Addr::unchecked("admin11111"));
+        assert_eq!(config.treasury_address, Addr::unchecked("admin11111"));
+        assert_eq!(config.protocol_fee_percentage, Uint128::zero());
+        assert_eq!(config.early_withdrawal_penalty_percentage, Uint128::zero());
+        assert_eq!(config.penalty_destination, PenaltyDestination::Burn);
+        assert_eq!(config.club_price, Uint128::from(CLUB_PRICE));
+        assert_eq!(config.club_buying_reward_amount, Uint128::from(100u128));
+        assert_eq!(config.club_locking_duration, 1814400u64);
+        assert_eq!(config.club_bonding_duration, 604800u64);
+        assert_eq!(config.owner_reward_percentage, Uint128::from(1u128));
+        assert_eq!(config.winner_club_reward_percentage, Uint128::from(19u128));
+        assert_eq!(config.all_stakers_reward_percentage, Uint128::from(80u128));
+        assert_eq!(config.pricing_model, PricingModel::Fixed);
+        assert_eq!(config.max_clubs_per_owner, 0u32);
+
+        // The new settings can be given on the way up
+        let mut deps = mock_dependencies(&[]);
+        set_contract_version(&mut deps.storage, CONTRACT_NAME, "0.9.1").unwrap();
+        deps.storage.set(CONFIG_KEY.as_bytes(),
+            br#"{"cw20_token_address":"cwtoken11111","admin_address":"admin11111"}"#);
+        migrate(deps.as_mut(), mock_env(), MigrateMsg {
+            treasury_address: Some("treasury11111".to_string()),
+            protocol_fee_percentage: Some(Uint128::from(5u128)),
+            early_withdrawal_penalty_percentage: Some(Uint128::from(10u128)),
+            penalty_destination: Some(PenaltyDestination::Treasury),
+        }).unwrap();
+        let config = CONFIG.load(&deps.storage).unwrap();
+        assert_eq!(config.treasury_address, Addr::unchecked("treasury11111"));
+        assert_eq!(config.protocol_fee_percentage, Uint128::from(5u128));
+        assert_eq!(config.early_withdrawal_penalty_percentage, Uint128::from(10u128));
+        assert_eq!(config.penalty_destination, PenaltyDestination::Treasury);
+
+        let mut deps = mock_dependencies(&[]);
+        set_contract_version(&mut deps.storage, CONTRACT_NAME, "0.9.1").unwrap();
+        deps.storage.set(CONFIG_KEY.as_bytes(),
+            br#"{"cw20_token_address":"cwtoken11111","admin_address":"admin11111"}"#);
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {
+            protocol_fee_percentage: Some(Uint128::from(101u128)),
+            ..MigrateMsg::default()
+        }).unwrap_err();
+        assert_eq!(err, ContractError::InvalidPercentage {
+            field: String::from("protocol_fee_percentage"),
+            value: Uint128::from(101u128),
+        });
+    }
+
+    #[test]
+    fn test_migrate_refuses_downgrade() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
+
+        set_contract_version(&mut deps.storage, CONTRACT_NAME, "99.0.0").unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap_err();
+        assert_eq!(err, ContractError::CannotMigrateVersion {
+            previous_version: "99.0.0".to_string(),
+            new_version: CONTRACT_VERSION.to_string(),
+        });
+
+        set_contract_version(&mut deps.storage, "crates.io:cw20-base", "0.9.1").unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap_err();
+        assert_eq!(err, ContractError::CannotMigrate {
+            previous_contract: "crates.io:cw20-base".to_string(),
+        });
+    }
+
+    #[test]
+    fn test_migrate_moves_stakes_to_per_staker_entries() {
+        let mut deps = mock_dependencies(&[]);
+        instantiate_contract(deps.as_mut());
+        set_contract_version(&mut deps.storage, CONTRACT_NAME, "0.10.0").unwrap();
+
+        let stake = |club: &str, staker: &str, amount: u128| ClubStakingDetails {
+            club_name: club.to_string(),
+            staker_address: staker.to_string(),
+            staked_amount: Uint128::from(amount),
+            reward_amount: Uint128::zero(),
+            ..Default::default()
+        };
+        CLUB_STAKING_DETAILS_V0_10.save(&mut deps.storage, "CLUB001".to_string(),
+            &vec![stake("CLUB001", "Staker0001", 30), stake("CLUB001", "Staker0002", 12)]).unwrap();
+        CLUB_STAKING_DETAILS_V0_10.save(&mut deps.storage, "CLUB002".to_string(),
+            &vec![stake("CLUB002", "Staker0001", 5)]).unwrap();
+
+        migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap();
+
+        assert_eq!(CLUB_STAKING_DETAILS_V0_10.may_load(&deps.storage, "CLUB001".to_string()).unwrap(), None);
+        assert_eq!(query_all_stakes(&deps.storage).unwrap().len(), 3);
+        assert_eq!(CLUB_STAKING_TOTALS.load(&deps.storage, "CLUB001".to_string()).unwrap(), Uint128::from(42u128));
+        assert_eq!(CLUB_STAKING_TOTALS.load(&deps.storage, "CLUB002".to_string()).unwrap(), Uint128::from(5u128));
+        assert_eq!(get_top_ranked_club(&deps.storage).unwrap(), Some(("CLUB001".to_string(), Uint128::from(42u128))));
+
+        let staker_stakes: Vec<ClubStakingDetails> = club_staking_details()
+            .idx
+            .staker
+            .prefix(b"Staker0001".to_vec())
+            .range(&deps.storage, None, None, Order::Ascending)
+            .map(|item| item.unwrap().1)
+            .collect();
+        assert_eq!(staker_stakes, vec![stake("CLUB001", "Staker0001", 30), stake("CLUB002", "Staker0001", 5)]);
+    }
 }
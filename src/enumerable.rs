@@ -1,8 +1,18 @@
-use cosmwasm_std::{Deps, Order, StdResult};
+use cosmwasm_std::{BlockInfo, Deps, Order, StdError, StdResult, Uint128};
 use cw20::{AllAccountsResponse, AllAllowancesResponse, AllowanceInfo};
 
-use crate::state::{ALLOWANCES};
-use cw_storage_plus::Bound;
+use crate::contract::settle_rewards;
+use crate::msg::{
+    BondsResponse, ClubOwnershipHistoryResponse, ClubRank, ClubRankingResponse,
+    ClubRegistryResponse, ClubsResponse, ListingsResponse, OffersResponse, StakesResponse,
+};
+use crate::state::{
+    club_staking_details, leaderboard_club_name, leaderboard_key, ClubBondingDetails, ClubInfo,
+    ClubListing, ClubOffer, ClubOwnershipDetails, ClubOwnershipRecord, ClubStakingDetails, ClubStatus, ALLOWANCES, CLUBS, CLUB_BONDING_DETAILS,
+    CLUB_LISTINGS, CLUB_OFFERS, CLUB_OWNERSHIP_HISTORY,
+    CLUB_LEADERBOARD, CLUB_OWNERSHIP_DETAILS, CLUB_STAKING_TOTALS,
+};
+use cw_storage_plus::{Bound, PrimaryKey};
 
 // settings for pagination
 const MAX_LIMIT: u32 = 30;
@@ -36,6 +46,295 @@ pub fn query_all_allowances(
     })
 }
 
+/// Stakes with their pending rewards, ordered by (club, staker).
+/// start_after is the (club, staker) of the last stake of the previous page.
+pub fn query_all_stakes(
+    deps: Deps,
+    club_name: Option<String>,
+    staker: Option<String>,
+    min_amount: Option<Uint128>,
+    start_after: Option<(String, String)>,
+    limit: Option<u32>,
+) -> StdResult<StakesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let min_amount = min_amount.unwrap_or_default();
+    let staker = staker
+        .map(|staker| deps.api.addr_validate(&staker))
+        .transpose()?;
+
+    let stakes: Box<dyn Iterator<Item = StdResult<(Vec<u8>, ClubStakingDetails)>> + '_> =
+        match (club_name, staker) {
+            (Some(club_name), Some(staker)) => {
+                // at most one stake, unless the previous page already returned it
+                let key = (club_name, staker.to_string());
+                let stake = match start_after {
+                    Some(start_after) if start_after >= key => None,
+                    _ => club_staking_details().may_load(deps.storage, key)?,
+                };
+                Box::new(stake.map(|stake| Ok((vec![], stake))).into_iter())
+            }
+            (Some(club_name), None) => {
+                // within a club the stakes are keyed by the staker alone
+                let start = start_after
+                    .filter(|(club, _)| *club == club_name)
+                    .map(|(_, staker)| Bound::exclusive(staker));
+                club_staking_details().prefix(club_name).range(
+                    deps.storage,
+                    start,
+                    None,
+                    Order::Ascending,
+                )
+            }
+            (None, Some(staker)) => {
+                let start = start_after.map(|key| Bound::exclusive(key.joined_key()));
+                club_staking_details()
+                    .idx
+                    .staker
+                    .prefix(staker.as_bytes().to_vec())
+                    .range(deps.storage, start, None, Order::Ascending)
+            }
+            (None, None) => {
+                let start = start_after.map(|key| Bound::exclusive(key.joined_key()));
+                club_staking_details().range(deps.storage, start, None, Order::Ascending)
+            }
+        };
+
+    let stakes: StdResult<Vec<ClubStakingDetails>> = stakes
+        .map(|item| {
+            let mut stake = item?.1;
+            settle_rewards(deps.storage, &mut stake)?;
+            Ok(stake)
+        })
+        .filter(|stake| match stake {
+            Ok(stake) => stake.staked_amount >= min_amount,
+            Err(_) => true,
+        })
+        .take(limit)
+        .collect();
+    Ok(StakesResponse { stakes: stakes? })
+}
+
+/// Bonds still waiting to be refunded, in club order and within a club in
+/// the order they were made. A bond is keyed by its club and its bond_id;
+/// start_after is the key of the last bond of the previous page, as
+/// returned in BondsResponse.last_key.
+pub fn query_all_bonds(
+    deps: Deps,
+    club_name: Option<String>,
+    bonder: Option<String>,
+    min_amount: Option<Uint128>,
+    start_after: Option<(String, u64)>,
+    limit: Option<u32>,
+) -> StdResult<BondsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let min_amount = min_amount.unwrap_or_default();
+    let bonder = bonder
+        .map(|bonder| deps.api.addr_validate(&bonder))
+        .transpose()?;
+
+    // A single club is loaded directly, otherwise the clubs are walked from
+    // the club of start_after on, only as far as the page reaches
+    let clubs: Box<dyn Iterator<Item = StdResult<(String, Vec<ClubBondingDetails>)>> + '_> =
+        match club_name {
+            Some(club_name) => Box::new(
+                CLUB_BONDING_DETAILS
+                    .may_load(deps.storage, club_name.clone())?
+                    .map(|bonds| Ok((club_name, bonds)))
+                    .into_iter(),
+            ),
+            None => {
+                let start = start_after
+                    .as_ref()
+                    .map(|(club_name, _)| Bound::inclusive(club_name.clone()));
+                Box::new(
+                    CLUB_BONDING_DETAILS
+                        .range(deps.storage, start, None, Order::Ascending)
+                        .map(|item| {
+                            let (k, bonds) = item?;
+                            Ok((String::from_utf8(k)?, bonds))
+                        }),
+                )
+            }
+        };
+
+    let mut bonds = Vec::new();
+    let mut last_key = None;
+    'clubs: for club in clubs {
+        let (club_name, club_bonds) = club?;
+        for bond in club_bonds {
+            let key = (club_name.clone(), bond.bond_id);
+            if let Some(start_after) = &start_after {
+                if key <= *start_after {
+                    continue;
+                }
+            }
+            if let Some(bonder) = &bonder {
+                if bond.bonder_address != bonder.as_str() {
+                    continue;
+                }
+            }
+            if bond.bonded_amount < min_amount {
+                continue;
+            }
+            bonds.push(bond);
+            last_key = Some(key);
+            if bonds.len() == limit {
+                break 'clubs;
+            }
+        }
+    }
+    Ok(BondsResponse { bonds, last_key })
+}
+
+/// Clubs that have been bought, ordered by name
+pub fn query_all_clubs(
+    deps: Deps,
+    owner: Option<String>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ClubsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let owner = owner
+        .map(|owner| deps.api.addr_validate(&owner))
+        .transpose()?;
+    let start = start_after.map(Bound::exclusive);
+
+    let clubs: StdResult<Vec<ClubOwnershipDetails>> = CLUB_OWNERSHIP_DETAILS
+        .range(deps.storage, start, None, Order::Ascending)
+        .map(|item| Ok(item?.1))
+        .filter(|club: &StdResult<ClubOwnershipDetails>| match (club, &owner) {
+            (Ok(club), Some(owner)) => club.owner_address == owner.as_str(),
+            _ => true,
+        })
+        .take(limit)
+        .collect();
+    Ok(ClubsResponse { clubs: clubs? })
+}
+
+/// Registered clubs, ordered by name
+pub fn query_club_registry(
+    deps: Deps,
+    status: Option<ClubStatus>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ClubRegistryResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let clubs: StdResult<Vec<ClubInfo>> = CLUBS
+        .range(deps.storage, start, None, Order::Ascending)
+        .map(|item| Ok(item?.1))
+        .filter(|club: &StdResult<ClubInfo>| match (club, &status) {
+            (Ok(club), Some(status)) => club.status == *status,
+            _ => true,
+        })
+        .take(limit)
+        .collect();
+    Ok(ClubRegistryResponse { clubs: clubs? })
+}
+
+/// Listings that can still be bought, ordered by club name
+pub fn query_club_listings(
+    deps: Deps,
+    block: &BlockInfo,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ListingsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let listings: StdResult<Vec<ClubListing>> = CLUB_LISTINGS
+        .range(deps.storage, start, None, Order::Ascending)
+        .map(|item| Ok(item?.1))
+        .filter(|listing: &StdResult<ClubListing>| match listing {
+            Ok(listing) => !listing.expiry.is_expired(block),
+            Err(_) => true,
+        })
+        .take(limit)
+        .collect();
+    Ok(ListingsResponse {
+        listings: listings?,
+    })
+}
+
+/// Offers on the club that can still be accepted, ordered by bidder
+pub fn query_club_offers(
+    deps: Deps,
+    block: &BlockInfo,
+    club_name: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<OffersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let offers: StdResult<Vec<ClubOffer>> = CLUB_OFFERS
+        .prefix(club_name)
+        .range(deps.storage, start, None, Order::Ascending)
+        .map(|item| Ok(item?.1))
+        .filter(|offer: &StdResult<ClubOffer>| match offer {
+            Ok(offer) => !offer.expiry.is_expired(block),
+            Err(_) => true,
+        })
+        .take(limit)
+        .collect();
+    Ok(OffersResponse { offers: offers? })
+}
+
+/// Ownership history of the club, oldest owner first
+pub fn query_club_ownership_history(
+    deps: Deps,
+    club_name: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ClubOwnershipHistoryResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|sequence| Bound::exclusive(sequence.to_be_bytes().to_vec()));
+
+    let history: StdResult<Vec<ClubOwnershipRecord>> = CLUB_OWNERSHIP_HISTORY
+        .prefix(club_name)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| Ok(item?.1))
+        .collect();
+    Ok(ClubOwnershipHistoryResponse { history: history? })
+}
+
+pub fn query_club_ranking(
+    deps: Deps,
+    start_after: Option<(String, u32)>,
+    limit: Option<u32>,
+) -> StdResult<ClubRankingResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let mut start = None;
+    // Ranks carry on from the rank the client got for start_after, counting
+    // the clubs before it would take a walk over the whole leaderboard
+    let mut clubs_before: u32 = 0;
+    if let Some((club_name, rank)) = start_after {
+        let total = CLUB_STAKING_TOTALS
+            .may_load(deps.storage, club_name.clone())?
+            .ok_or_else(|| StdError::generic_err("Club is not on the leaderboard"))?;
+        let start_key = leaderboard_key(club_name, total).joined_key();
+        clubs_before = rank;
+        start = Some(Bound::exclusive(start_key));
+    }
+
+    let clubs: StdResult<Vec<ClubRank>> = CLUB_LEADERBOARD
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .enumerate()
+        .map(|(i, item)| {
+            let (k, total_staked) = item?;
+            Ok(ClubRank {
+                rank: clubs_before + i as u32 + 1,
+                club_name: leaderboard_club_name(&k)?,
+                total_staked,
+            })
+        })
+        .collect();
+    Ok(ClubRankingResponse { clubs: clubs? })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
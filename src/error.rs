@@ -1,4 +1,6 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Timestamp, Uint128};
+
+use crate::state::ClubStatus;
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -12,27 +14,132 @@ pub enum ContractError {
     #[error("Cannot set to own account")]
     CannotSetOwnAccount {},
 
-    #[error("Invalid zero amount")]
-    InvalidZeroAmount {},
-
     #[error("Allowance is expired")]
     Expired {},
 
     #[error("No allowance for this account")]
     NoAllowance {},
 
-    // #[error("No vesting details for this account")]
-    // NoVestingDetails (String),
+    #[error("{field} cannot exceed 100, got {value}")]
+    InvalidPercentage { field: String, value: Uint128 },
+
+    #[error("Reward split percentages must add up to 100, got {total}")]
+    InvalidRewardSplit { total: Uint128 },
+
+    #[error("Cannot migrate from contract {previous_contract}")]
+    CannotMigrate { previous_contract: String },
+
+    #[error("Cannot migrate from version {previous_version} to older version {new_version}")]
+    CannotMigrateVersion {
+        previous_version: String,
+        new_version: String,
+    },
+
+    #[error("No admin change has been proposed")]
+    NoPendingAdmin {},
+
+    #[error("Club {club} not found")]
+    ClubNotFound { club: String },
+
+    #[error("Invalid club name {club:?}: {reason}")]
+    InvalidClubName { club: String, reason: String },
 
-    #[error("Minting cannot exceed the cap")]
-    CannotExceedCap {},
+    #[error("Club {club} and another stored club both normalize to {normalized}")]
+    ClubNameCollision { club: String, normalized: String },
+
+    #[error("Club {club} already exists")]
+    ClubAlreadyExists { club: String },
+
+    #[error("Club {club} is {status}")]
+    ClubNotActive { club: String, status: ClubStatus },
+
+    #[error("Club {club} is retired for good")]
+    ClubRetired { club: String },
 
     #[error("Logo binary data exceeds 5KB limit")]
     LogoTooBig {},
 
-    #[error("Invalid xml preamble for SVG")]
-    InvalidXmlPreamble {},
+    #[error("Display name exceeds 64 bytes limit")]
+    DisplayNameTooLong {},
+
+    #[error("Description exceeds 1024 bytes limit")]
+    DescriptionTooLong {},
+
+    #[error("{address} is not the owner of club {club}")]
+    NotClubOwner { club: String, address: String },
+
+    #[error("Owner has not released club {club}")]
+    ClubNotReleased { club: String },
+
+    #[error("Club {club} is locked until {until}")]
+    ClubLocked { club: String, until: Timestamp },
+
+    #[error("Club {club} is being auctioned")]
+    ClubInAuction { club: String },
+
+    #[error("No auction running for club {club}")]
+    NoAuction { club: String },
+
+    #[error("Auction of club {club} has ended")]
+    AuctionEnded { club: String },
+
+    #[error("Auction of club {club} only ends at {ends_at}")]
+    AuctionNotEnded { club: String, ends_at: Timestamp },
+
+    #[error("Reserve price {reserve_price} is below the floor price {minimum}")]
+    ReservePriceTooLow {
+        reserve_price: Uint128,
+        minimum: Uint128,
+    },
+
+    #[error("Club {club} is listed for sale")]
+    ClubListed { club: String },
+
+    #[error("Asking price {asking_price} is below the floor price {minimum}")]
+    AskingPriceTooLow {
+        asking_price: Uint128,
+        minimum: Uint128,
+    },
+
+    #[error("No offer from {bidder} on club {club}")]
+    NoOffer { club: String, bidder: String },
+
+    #[error("Auction duration must be greater than zero")]
+    InvalidAuctionDuration {},
+
+    #[error("Minimum bid increment must be greater than zero")]
+    InvalidBidIncrement {},
+
+    #[error("Floor price of club {club} overflows")]
+    PriceOverflow { club: String },
+
+    #[error("Bid {bid} is below the minimum bid {minimum}")]
+    BidTooLow { minimum: Uint128, bid: Uint128 },
+
+    #[error("{owner} already owns the maximum of {max} clubs")]
+    TooManyClubs { owner: String, max: u32 },
+
+    #[error("Insufficient funds sent to buy the club: required {required}, sent {sent}")]
+    InsufficientFunds { required: Uint128, sent: Uint128 },
+
+    #[error("Withdrawal amount {requested} exceeds the staked amount {staked}")]
+    InsufficientStake { requested: Uint128, staked: Uint128 },
+
+    #[error("Amount must be greater than zero")]
+    InvalidZeroAmount {},
+
+    #[error("Insufficient rewards: requested {requested}, available {available}")]
+    InsufficientRewards {
+        requested: Uint128,
+        available: Uint128,
+    },
+
+    #[error("Contract balance {available} is insufficient to pay out {required}")]
+    InsufficientContractBalance {
+        required: Uint128,
+        available: Uint128,
+    },
 
-    #[error("Invalid png header")]
-    InvalidPngHeader {},
+    #[error("No matured bonds to claim")]
+    NoMaturedBonds {},
 }
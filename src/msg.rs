@@ -1,8 +1,23 @@
-use cosmwasm_std::{Binary, Uint128};
+use cosmwasm_std::{Binary, Timestamp, Uint128};
 use cw0::Expiration;
-use cw20::Logo;
+use cw20::{Cw20ReceiveMsg, Logo};
 use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::state::{
+    ClubBondingDetails, ClubInfo, ClubListing, ClubOffer, ClubOwnershipDetails,
+    ClubOwnershipRecord, ClubStakingDetails, ClubStatus,
+    PenaltyDestination, PendingAdmin, PricingModel, Role,
+};
+
+/// Lets an Option<Option<T>> field tell a missing field (None) apart from null (Some(None))
+fn deserialize_some<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
 
 #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub struct InstantiateMarketingInfo {
@@ -16,20 +31,142 @@ pub struct InstantiateMarketingInfo {
 pub struct InstantiateMsg {
     pub cw20_token_address: String,
     pub admin_address: String,
+    pub treasury_address: String,
+    pub protocol_fee_percentage: Uint128,
+    pub early_withdrawal_penalty_percentage: Uint128,
+    pub penalty_destination: PenaltyDestination,
+    pub club_price: Uint128,
+    pub club_buying_reward_amount: Uint128,
+    pub club_staking_reward_amount: Uint128,
+    pub club_locking_duration: u64,
+    pub club_bonding_duration: u64,
+    /// owner, winner club and all stakers percentages must add up to 100
+    pub owner_reward_percentage: Uint128,
+    pub winner_club_reward_percentage: Uint128,
+    pub all_stakers_reward_percentage: Uint128,
+    /// Paid to the treasury by anyone creating a club through the receive hook
+    pub club_creation_fee: Uint128,
+    /// Bids this close to the end of an auction, in seconds, extend it by as much
+    pub auction_extension_duration: u64,
+    pub pricing_model: PricingModel,
+    /// Most clubs one address may own at the same time, 0 for no limit
+    pub max_clubs_per_owner: u32,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
-    BuyAClub {
-        buyer: String,
-        seller: String,
+    /// Entry point for CW20 tokens sent to this contract. The embedded msg
+    /// must be a ReceiveMsg describing what the tokens are for.
+    Receive(Cw20ReceiveMsg),
+    /// Only with admin. Proposes a new admin, which only takes over once it
+    /// sends AcceptAdmin before the expiry (never expires if unset).
+    ProposeNewAdmin {
+        address: String,
+        expiry: Option<Expiration>,
+    },
+    /// Only with the proposed admin. Completes the admin change.
+    AcceptAdmin {},
+    /// Only with admin. Drops the pending admin proposal.
+    CancelAdminProposal {},
+    /// Only with admin. Gives the address an operator role.
+    GrantRole {
+        address: String,
+        role: Role,
+    },
+    /// Only with admin. Takes an operator role away from the address.
+    RevokeRole {
+        address: String,
+        role: Role,
+    },
+    /// Only with admin. Fields left as None keep their current value.
+    UpdateConfig {
+        treasury_address: Option<String>,
+        protocol_fee_percentage: Option<Uint128>,
+        early_withdrawal_penalty_percentage: Option<Uint128>,
+        penalty_destination: Option<PenaltyDestination>,
+        club_price: Option<Uint128>,
+        club_buying_reward_amount: Option<Uint128>,
+        club_staking_reward_amount: Option<Uint128>,
+        club_locking_duration: Option<u64>,
+        club_bonding_duration: Option<u64>,
+        owner_reward_percentage: Option<Uint128>,
+        winner_club_reward_percentage: Option<Uint128>,
+        all_stakers_reward_percentage: Option<Uint128>,
+        club_creation_fee: Option<Uint128>,
+        auction_extension_duration: Option<u64>,
+        pricing_model: Option<PricingModel>,
+        max_clubs_per_owner: Option<u32>,
+    },
+    /// Only with admin. Adds an active club to the registry free of charge.
+    /// Anyone else creates clubs through ReceiveMsg::CreateClub.
+    CreateClub {
         club_name: String,
+        display_name: String,
+        description: Option<String>,
+        logo: Option<Logo>,
+    },
+    /// Only with admin. Fields left as None keep their current value.
+    /// Send description as Some(None) (JSON null) to clear it.
+    UpdateClubMetadata {
+        club_name: String,
+        display_name: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "deserialize_some")]
+        description: Option<Option<String>>,
+        logo: Option<Logo>,
+    },
+    /// Only with admin. Paused and retired clubs cannot be bought or staked on.
+    /// Retiring a club is final.
+    SetClubStatus {
+        club_name: String,
+        status: ClubStatus,
     },
     ReleaseClub {
         owner: String,
         club_name: String,
     },
+    /// Only with the owner of a released club. Puts the club up for an
+    /// English auction instead of selling it at the fixed club price.
+    /// Bids are placed through ReceiveMsg::PlaceBid.
+    StartAuction {
+        club_name: String,
+        /// At least the floor price of the club, see QueryMsg::ClubPrice
+        reserve_price: Uint128,
+        /// Every bid after the first must beat the highest bid by at least
+        /// this much, so it cannot be zero
+        min_bid_increment: Uint128,
+        /// Seconds until the auction ends, not counting extensions
+        duration: u64,
+    },
+    /// Ends an auction past its end time. The highest bidder becomes the
+    /// owner and the seller is paid as on a regular sale.
+    SettleAuction {
+        club_name: String,
+    },
+    /// Only with the owner of a club past its locking period. Releases the
+    /// club for sale at the asking price until the expiry (never if unset).
+    /// An expired listing takes the club off sale again.
+    ListClub {
+        club_name: String,
+        /// At least the floor price of the club, see QueryMsg::ClubPrice
+        asking_price: Uint128,
+        expiry: Option<Expiration>,
+    },
+    /// Only with the owner. Takes a listed or released club off sale.
+    CancelListing {
+        club_name: String,
+    },
+    /// Only with the owner of a club past its locking period. Sells the club
+    /// to the bidder for their offer, made through ReceiveMsg::MakeOffer.
+    /// The offer must be at least the floor price of the club.
+    AcceptOffer {
+        club_name: String,
+        bidder: String,
+    },
+    /// Refunds the offer of the sender on the club.
+    WithdrawOffer {
+        club_name: String,
+    },
     ClaimOwnerRewards {
         owner: String,
         club_name: String,
@@ -40,18 +177,25 @@ pub enum ExecuteMsg {
         club_name: String,
         amount: Uint128,
     },
-    StakeOnAClub {
-        staker: String,
-        club_name: String,
-        amount: Uint128,
-    },
     StakeWithdrawFromAClub {
         staker: String,
         club_name: String,
         amount: Uint128,
         immediate_withdrawal: bool,
     },
-    PeriodicallyRefundStakeouts {},
+    /// Only with admin or a refund keeper. Refunds the matured bonds of up
+    /// to limit clubs after start_after. When the page is full the response
+    /// carries a last_club attribute to continue from. Bonds not covered by
+    /// the bonder's contract wallet are left in place.
+    PeriodicallyRefundStakeouts {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Refunds every matured bond of the sender, either in the given club
+    /// or in all clubs. Does not need to wait for PeriodicallyRefundStakeouts.
+    ClaimUnbonded {
+        club_name: Option<String>,
+    },
     SetRewardAmount {
         amount: Uint128,
     },
@@ -88,6 +232,55 @@ pub enum ExecuteMsg {
     },
 }
 
+/// Only read when migrating from before 0.10.0, whose Config held nothing
+/// but the token and admin addresses. The economics of the new Config keep
+/// the values 0.9.1 had hard coded; the settings below are new and default
+/// to leaving 0.9.1 behaviour unchanged.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct MigrateMsg {
+    /// Defaults to the admin address
+    pub treasury_address: Option<String>,
+    /// Defaults to 0
+    pub protocol_fee_percentage: Option<Uint128>,
+    /// Defaults to 0
+    pub early_withdrawal_penalty_percentage: Option<Uint128>,
+    /// Defaults to Burn
+    pub penalty_destination: Option<PenaltyDestination>,
+}
+
+/// Hook messages carried in Cw20ReceiveMsg.msg. The buyer or staker is the
+/// cw20 sender and the amount is whatever was actually transferred.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiveMsg {
+    StakeOnAClub {
+        club_name: String,
+    },
+    BuyAClub {
+        seller: String,
+        club_name: String,
+    },
+    /// Bids the amount on the running auction of the club. The bid is held
+    /// by the contract and refunded as soon as it is outbid.
+    PlaceBid {
+        club_name: String,
+    },
+    /// Offers the amount for an owned club. The offer is held by the
+    /// contract until the owner accepts it or the bidder withdraws it.
+    MakeOffer {
+        club_name: String,
+        expiry: Option<Expiration>,
+    },
+    /// Adds an active club to the registry. The amount must cover
+    /// Config.club_creation_fee, anything above it is refunded.
+    CreateClub {
+        club_name: String,
+        display_name: String,
+        description: Option<String>,
+        logo: Option<Logo>,
+    },
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
@@ -106,21 +299,329 @@ pub enum QueryMsg {
         start_after: Option<String>,
         limit: Option<u32>,
     },
-    /// Returns the current state of vesting information for the given address.
-    /// Return type: StakingDetails.
+    /// Returns the stakes on the given club with their pending rewards.
+    /// Return type: StakesResponse.
     ClubStakingDetails {
         club_name: String,
     },
     /// Returns the current state of withdrawn tokens that are locked for 
-    /// BONDING_DURATION = 7 days (before being credited back) for the given address.
-    /// Return type: BondingDetails.
+    /// BONDING_DURATION = 7 days (before being credited back) for the given club.
+    /// Return type: BondsResponse.
     ClubBondingDetails {
         club_name: String,
     },
+    /// Returns the current owner of the given club.
+    /// Return type: ClubOwnershipDetailsResponse.
     ClubOwnershipDetails {
         club_name: String,
     },
-    AllStakes {},
-    GetClubRankingByStakes {},
+    /// Returns the stakes with their pending rewards, optionally only those
+    /// of one club or staker and of at least min_amount. Supports pagination,
+    /// start_after being the (club name, staker) of the last stake returned.
+    /// Return type: StakesResponse.
+    AllStakes {
+        club_name: Option<String>,
+        staker: Option<String>,
+        min_amount: Option<Uint128>,
+        start_after: Option<(String, String)>,
+        limit: Option<u32>,
+    },
+    /// Returns the bonds waiting to be refunded, optionally only those of one
+    /// club or bonder and of at least min_amount. Supports pagination over
+    /// bonds, start_after being the last_key of the previous page: the club
+    /// and the bond_id of the last bond returned.
+    /// Return type: BondsResponse.
+    AllBonds {
+        club_name: Option<String>,
+        bonder: Option<String>,
+        min_amount: Option<Uint128>,
+        start_after: Option<(String, u64)>,
+        limit: Option<u32>,
+    },
+    /// Returns the ownership details of the clubs bought so far, optionally
+    /// only those of one owner. Supports pagination.
+    /// Return type: ClubsResponse.
+    AllClubs {
+        owner: Option<String>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns the registry entry of the given club.
+    /// Return type: ClubResponse.
+    Club {
+        club_name: String,
+    },
+    /// Returns the running auction of the given club.
+    /// Return type: ClubAuctionResponse.
+    ClubAuction {
+        club_name: String,
+    },
+    /// Returns the ownership details of every club the address owns.
+    /// Return type: OwnedClubsResponse.
+    OwnedClubs {
+        owner: String,
+    },
+    /// Returns every owner the club has had, oldest first. Supports
+    /// pagination, start_after being the sequence of the last record seen.
+    /// Return type: ClubOwnershipHistoryResponse.
+    ClubOwnershipHistory {
+        club_name: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns what buying the club costs right now: the asking price if it
+    /// is listed, otherwise the price of Config.pricing_model. Under the
+    /// Dynamic model that is
+    /// max(club_price, last price paid * resale_multiplier) + total staked on the club * stake_multiplier,
+    /// the stake term being added to the price rather than scaling it.
+    /// Return type: ClubPriceResponse.
+    ClubPrice {
+        club_name: String,
+    },
+    /// Returns the listings that have not expired, ordered by club name.
+    /// Supports pagination.
+    /// Return type: ListingsResponse.
+    ClubListings {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns the offers on the club that have not expired, ordered by
+    /// bidder. Supports pagination.
+    /// Return type: OffersResponse.
+    ClubOffers {
+        club_name: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns the registered clubs, optionally only those in the given
+    /// status. Supports pagination.
+    /// Return type: ClubRegistryResponse.
+    ClubRegistry {
+        status: Option<ClubStatus>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns the clubs ordered by their total stake, highest first.
+    /// Supports pagination, start_after being the club name and rank of the
+    /// last club of the previous page.
+    /// Return type: ClubRankingResponse.
+    GetClubRankingByStakes {
+        start_after: Option<(String, u32)>,
+        limit: Option<u32>,
+    },
+    /// Returns the reward waiting to be distributed.
+    /// Return type: RewardAmountResponse.
     RewardAmount {},
+    /// Returns the current economics and addresses.
+    /// Return type: ConfigResponse.
+    Config {},
+    /// Returns every address holding an operator role.
+    /// Return type: RolesResponse.
+    Roles {},
+    /// Returns everything the address has at stake: its stakes with pending
+    /// rewards, its bonds, and the clubs it owns or used to own.
+    /// Return type: StakerPortfolioResponse.
+    StakerPortfolio {
+        staker: String,
+    },
+    /// Returns the admin proposed through ProposeNewAdmin, if any.
+    /// Return type: PendingAdminResponse.
+    PendingAdmin {},
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct StakesResponse {
+    pub stakes: Vec<ClubStakingDetails>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct BondsResponse {
+    pub bonds: Vec<ClubBondingDetails>,
+    /// (club name, bond_id) of the last bond returned by AllBonds, to pass
+    /// as start_after for the next page
+    pub last_key: Option<(String, u64)>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ClubsResponse {
+    pub clubs: Vec<ClubOwnershipDetails>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct OwnedClubsResponse {
+    pub owner: String,
+    pub clubs: Vec<ClubOwnershipDetails>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ClubOwnershipHistoryResponse {
+    pub history: Vec<ClubOwnershipRecord>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ClubPriceResponse {
+    pub club_name: String,
+    /// False while BuyAClub would refuse the club: it is not active, is in
+    /// auction or its owner has not released it. The prices are then only
+    /// indicative.
+    pub purchasable: bool,
+    /// Amount a buyer has to send with BuyAClub
+    pub price: Uint128,
+    /// Lowest asking price, auction reserve or accepted offer for the club
+    pub floor_price: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ListingsResponse {
+    pub listings: Vec<ClubListing>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct OffersResponse {
+    pub offers: Vec<ClubOffer>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ClubRegistryResponse {
+    pub clubs: Vec<ClubInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ClubRankingResponse {
+    pub clubs: Vec<ClubRank>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ClubRank {
+    /// Position on the leaderboard, starting at 1
+    pub rank: u32,
+    pub club_name: String,
+    pub total_staked: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct RewardAmountResponse {
+    /// Amount handed out by every reward distribution
+    pub amount: Uint128,
+    /// Penalties added to the next reward distribution only
+    pub penalty_pool: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ConfigResponse {
+    pub cw20_token_address: String,
+    pub admin_address: String,
+    pub treasury_address: String,
+    pub protocol_fee_percentage: Uint128,
+    pub early_withdrawal_penalty_percentage: Uint128,
+    pub penalty_destination: PenaltyDestination,
+    pub club_price: Uint128,
+    pub club_buying_reward_amount: Uint128,
+    pub club_staking_reward_amount: Uint128,
+    pub club_locking_duration: u64,
+    pub club_bonding_duration: u64,
+    pub owner_reward_percentage: Uint128,
+    pub winner_club_reward_percentage: Uint128,
+    pub all_stakers_reward_percentage: Uint128,
+    pub club_creation_fee: Uint128,
+    pub auction_extension_duration: u64,
+    pub pricing_model: PricingModel,
+    pub max_clubs_per_owner: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ClubOwnershipDetailsResponse {
+    pub club_name: String,
+    pub owner_address: String,
+    /// When the owner bought the club
+    pub start_timestamp: Timestamp,
+    /// Seconds after start_timestamp during which the club cannot be sold
+    pub locking_period: u64,
+    pub price_paid: Uint128,
+    /// Owner rewards not claimed yet
+    pub reward_amount: Uint128,
+    /// Whether the owner has put the club up for sale
+    pub owner_released: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ClubResponse {
+    pub club_name: String,
+    pub display_name: String,
+    pub description: Option<String>,
+    pub logo: Option<Logo>,
+    pub status: ClubStatus,
+    pub creator: String,
+    pub created_at: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ClubAuctionResponse {
+    pub club_name: String,
+    pub seller: String,
+    pub reserve_price: Uint128,
+    pub min_bid_increment: Uint128,
+    pub start_timestamp: Timestamp,
+    pub end_timestamp: Timestamp,
+    pub highest_bid: Option<HighestBid>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct HighestBid {
+    pub bidder: String,
+    pub amount: Uint128,
+    pub placed_at: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct PendingAdminResponse {
+    pub pending_admin: Option<PendingAdmin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct RoleHolder {
+    pub address: String,
+    pub roles: Vec<Role>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct RolesResponse {
+    pub holders: Vec<RoleHolder>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct PortfolioStake {
+    pub club_name: String,
+    pub staked_amount: Uint128,
+    /// Rewards claimable through ClaimRewards
+    pub pending_rewards: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct PortfolioBond {
+    pub club_name: String,
+    pub bonded_amount: Uint128,
+    /// The bond can be claimed or refunded from this time on
+    pub matures_at: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct PortfolioClub {
+    pub club_name: String,
+    /// Owner rewards claimable for this club
+    pub reward_amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct StakerPortfolioResponse {
+    pub staker: String,
+    pub stakes: Vec<PortfolioStake>,
+    pub bonds: Vec<PortfolioBond>,
+    pub owned_clubs: Vec<PortfolioClub>,
+    pub previously_owned_clubs: Vec<PortfolioClub>,
+    pub total_staked: Uint128,
+    pub total_pending_rewards: Uint128,
+    pub total_bonded: Uint128,
+    /// Owner rewards over both owned and previously owned clubs
+    pub total_owner_rewards: Uint128,
 }
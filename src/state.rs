@@ -1,20 +1,166 @@
+use std::fmt;
+
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Addr, Timestamp, Uint128};
-use cw_storage_plus::{Item, Map};
+use cosmwasm_std::{Addr, Decimal, Empty, StdResult, Timestamp, Uint128};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex, U64Key};
 
-use cw20::AllowanceResponse;
+use cw20::{AllowanceResponse, Expiration, Logo};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
     pub cw20_token_address: Addr,
     pub admin_address: Addr,
+    /// Receives the protocol fee on club sales
+    pub treasury_address: Addr,
+    /// Share of every club sale, in percent, routed to the treasury
+    pub protocol_fee_percentage: Uint128,
+    /// Share of an immediate withdrawal, in percent, kept as a penalty
+    pub early_withdrawal_penalty_percentage: Uint128,
+    /// Where the early withdrawal penalty ends up
+    pub penalty_destination: PenaltyDestination,
+    /// Price of a club in quantity of tokens
+    pub club_price: Uint128,
+    /// Reward to club owner for buying
+    pub club_buying_reward_amount: Uint128,
+    /// Initial reward of a new stake
+    pub club_staking_reward_amount: Uint128,
+    /// Locking period in seconds after buying a club (21 days)
+    pub club_locking_duration: u64,
+    /// Bonding period in seconds after withdrawing a stake (7 days)
+    pub club_bonding_duration: u64,
+    /// Percentage of each reward distribution given to the owner of the top club
+    pub owner_reward_percentage: Uint128,
+    /// Percentage of each reward distribution shared by the stakers of the top club
+    pub winner_club_reward_percentage: Uint128,
+    /// Percentage of each reward distribution shared by all stakers
+    pub all_stakers_reward_percentage: Uint128,
+    /// Price, paid to the treasury, of creating a club through the receive hook
+    #[serde(default)]
+    pub club_creation_fee: Uint128,
+    /// A bid placed less than this many seconds before the end of an
+    /// auction extends the auction to this many seconds after the bid
+    #[serde(default)]
+    pub auction_extension_duration: u64,
+    /// How the price of a club that is not listed is worked out
+    #[serde(default)]
+    pub pricing_model: PricingModel,
+    /// Most clubs one address may own at the same time, 0 for no limit
+    #[serde(default)]
+    pub max_clubs_per_owner: u32,
+}
+
+/// Price of a club bought without a listing, and the floor of listings,
+/// auction reserves and accepted offers
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PricingModel {
+    /// Every club costs Config.club_price
+    Fixed,
+    /// The higher of Config.club_price and the last price paid for the club
+    /// times resale_multiplier, plus the total stake on the club times
+    /// stake_multiplier
+    Dynamic {
+        resale_multiplier: Decimal,
+        stake_multiplier: Decimal,
+    },
+}
+
+impl Default for PricingModel {
+    fn default() -> Self {
+        PricingModel::Fixed
+    }
+}
+
+/// Sink for the penalty deducted from immediate withdrawals
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PenaltyDestination {
+    /// Burn the tokens through the cw20 contract
+    Burn,
+    /// Transfer the tokens to Config.treasury_address
+    Treasury,
+    /// Keep the tokens and add them to the staking reward
+    RewardPool,
+}
+
+/// Operator roles the admin can hand out for privileged, recurring tasks
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// May set the reward amount and run the reward distribution
+    RewardDistributor,
+    /// May run the periodic refund of matured bonds
+    RefundKeeper,
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Role::RewardDistributor => write!(f, "reward_distributor"),
+            Role::RefundKeeper => write!(f, "refund_keeper"),
+        }
+    }
+}
+
+/// Lifecycle of a club in the registry
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ClubStatus {
+    /// Can be bought and staked on
+    Active,
+    /// Temporarily closed to buying and staking
+    Paused,
+    /// Permanently closed to buying and staking
+    Retired,
+}
+
+impl fmt::Display for ClubStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ClubStatus::Active => write!(f, "active"),
+            ClubStatus::Paused => write!(f, "paused"),
+            ClubStatus::Retired => write!(f, "retired"),
+        }
+    }
+}
+
+/// A club known to the contract, created by the admin or through a paid CreateClub
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClubInfo {
+    pub club_name: String,
+    pub display_name: String,
+    pub description: Option<String>,
+    pub logo: Option<Logo>,
+    pub status: ClubStatus,
+    pub creator: Addr,
+    pub created_at: Timestamp,
 }
 
 pub const CONFIG_KEY: &str = "config";
 pub const CONFIG: Item<Config> = Item::new(CONFIG_KEY);
 
+/// Config as stored before 0.10.0. Only read by migrate.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyConfig {
+    pub cw20_token_address: Addr,
+    pub admin_address: Addr,
+}
+
+/// Pre 0.10.0 view of CONFIG, same key
+pub const LEGACY_CONFIG: Item<LegacyConfig> = Item::new(CONFIG_KEY);
+
+/// Admin proposed by the current admin, waiting to accept the role
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingAdmin {
+    pub address: Addr,
+    /// The proposal can no longer be accepted after this
+    pub expiry: Expiration,
+}
+
+pub const PENDING_ADMIN: Item<PendingAdmin> = Item::new("pending_admin");
+
 /// This is used for saving various vesting details
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
 #[serde(rename_all = "snake_case")]
@@ -62,16 +208,30 @@ pub struct ClubStakingDetails {
 
     pub staker_address: String,
 
-    /// The system timestamp to be used as starting point of staking
-    pub staking_start_timestamp: Timestamp,
-
     /// staked amount in quantity of tokens
     pub staked_amount: Uint128,
 
-    /// Duration of staking expressed in seconds
-    pub staking_duration: u64,
+    /// reward amount in quantity of tokens, as of the last settlement
+    pub reward_amount: Uint128,
 
-    /// reward amount in quantity of tokens
+    /// GLOBAL_REWARD_INDEX when the reward_amount was last settled
+    #[serde(default)]
+    pub global_reward_index: Decimal,
+
+    /// CLUB_REWARD_INDEX of the club when the reward_amount was last settled
+    #[serde(default)]
+    pub club_reward_index: Decimal,
+}
+
+/// ClubStakingDetails as stored before 0.10.0. Only read by migrate.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct LegacyClubStakingDetails {
+    pub club_name: String,
+    pub staker_address: String,
+    pub staking_start_timestamp: Timestamp,
+    pub staked_amount: Uint128,
+    pub staking_duration: u64,
     pub reward_amount: Uint128,
 }
 
@@ -91,18 +251,140 @@ pub struct ClubBondingDetails {
 
     /// Duration of bonding expressed in seconds
     pub bonding_duration: u64,
+
+    /// Id of the bond within its club. Ids are handed out in the order the
+    /// bonds are made and never reused, see CLUB_BOND_SEQUENCE.
+    #[serde(default)]
+    pub bond_id: u64,
 }
 
 pub const ALLOWANCES: Map<(&Addr, &Addr), AllowanceResponse> = Map::new("allowance");
 
+/// Registry of clubs. Only clubs in here can be bought or staked on.
+pub const CLUBS: Map<String, ClubInfo> = Map::new("clubs");
+
+/// A bid escrowed by the contract until it is outbid or the auction settles
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AuctionBid {
+    pub bidder: Addr,
+    pub amount: Uint128,
+    pub placed_at: Timestamp,
+}
+
+/// English auction of a released club, started by its owner
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClubAuction {
+    pub club_name: String,
+    pub seller: Addr,
+    /// Lowest acceptable first bid
+    pub reserve_price: Uint128,
+    /// Every following bid must beat the highest bid by at least this much
+    pub min_bid_increment: Uint128,
+    pub start_timestamp: Timestamp,
+    /// Moves out when bids come in near the end
+    pub end_timestamp: Timestamp,
+    pub highest_bid: Option<AuctionBid>,
+}
+
+/// Map of clubs and their running auction. While a club is in here it
+/// cannot be bought directly.
+pub const CLUB_AUCTIONS: Map<String, ClubAuction> = Map::new("club_auctions");
+
+/// A released club offered for sale by its owner at a price of their choosing
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClubListing {
+    pub club_name: String,
+    pub seller: Addr,
+    /// Price a buyer pays instead of Config.club_price
+    pub asking_price: Uint128,
+    /// The club cannot be bought through the listing after this
+    pub expiry: Expiration,
+    pub listed_at: Timestamp,
+}
+
+/// Map of clubs and their listing
+pub const CLUB_LISTINGS: Map<String, ClubListing> = Map::new("club_listings");
+
+/// An offer for a club, escrowed by the contract until it is accepted or withdrawn
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClubOffer {
+    pub club_name: String,
+    pub bidder: Addr,
+    pub amount: Uint128,
+    /// The offer cannot be accepted after this, only withdrawn
+    pub expiry: Expiration,
+    pub made_at: Timestamp,
+}
+
+/// Map of offers. the key is (club name, bidder address)
+pub const CLUB_OFFERS: Map<(String, String), ClubOffer> = Map::new("club_offers");
+
 /// Map of clubs and its owners. the key is club name and the
 /// ClubOwnershipDetails will contain information about the owner
 pub const CLUB_OWNERSHIP_DETAILS: Map<String, ClubOwnershipDetails> =
     Map::new("club_ownership_details");
 
-/// Map of clubs and its stakers. the key is club name and the
-/// ClubStakingDetails will contain information about the stakers and amount staked
-pub const CLUB_STAKING_DETAILS: Map<String, Vec<ClubStakingDetails>> =
+pub struct ClubStakingIndexes<'a> {
+    /// (staker, pk) -> stake, to find the stakes of an address across clubs
+    pub staker: MultiIndex<'a, (Vec<u8>, Vec<u8>), ClubStakingDetails>,
+}
+
+impl<'a> IndexList<ClubStakingDetails> for ClubStakingIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<ClubStakingDetails>> + '_> {
+        let v: Vec<&dyn Index<ClubStakingDetails>> = vec![&self.staker];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Map of stakes. the key is (club name, staker address) and the
+/// ClubStakingDetails will contain information about the amount staked
+pub fn club_staking_details<'a>(
+) -> IndexedMap<'a, (String, String), ClubStakingDetails, ClubStakingIndexes<'a>> {
+    let indexes = ClubStakingIndexes {
+        staker: MultiIndex::new(
+            |stake, pk| (stake.staker_address.as_bytes().to_vec(), pk),
+            "club_stakes",
+            "club_stakes__staker",
+        ),
+    };
+    IndexedMap::new("club_stakes", indexes)
+}
+
+/// Map of clubs and the sum of all stakes in them
+pub const CLUB_STAKING_TOTALS: Map<String, Uint128> = Map::new("club_staking_totals");
+
+/// Clubs ordered by their total stake, highest first. The key is
+/// (u128::MAX - total stake as big endian bytes, club name), so ranging in
+/// ascending order walks the ranking with ties broken by club name.
+/// The value is the total stake of the club.
+pub const CLUB_LEADERBOARD: Map<(Vec<u8>, String), Uint128> = Map::new("club_leaderboard");
+
+pub fn leaderboard_key(club_name: String, total_staked: Uint128) -> (Vec<u8>, String) {
+    ((u128::MAX - total_staked.u128()).to_be_bytes().to_vec(), club_name)
+}
+
+/// Recovers the club name from a raw CLUB_LEADERBOARD key, which is the
+/// length prefixed 16 byte stake followed by the club name
+pub fn leaderboard_club_name(raw_key: &[u8]) -> StdResult<String> {
+    Ok(String::from_utf8(raw_key[18..].to_vec())?)
+}
+
+/// Sum of all stakes over all clubs
+pub const TOTAL_STAKED: Item<Uint128> = Item::new("total_staked");
+
+/// Reward per staked token handed out to the stakers of all clubs so far
+pub const GLOBAL_REWARD_INDEX: Item<Decimal> = Item::new("global_reward_index");
+
+/// Map of clubs and the reward per staked token handed out to the stakers
+/// of that club as the winner club so far
+pub const CLUB_REWARD_INDEX: Map<String, Decimal> = Map::new("club_reward_index");
+
+/// Pre 0.11.0 layout of the stakes, one vector of stakes per club
+pub const CLUB_STAKING_DETAILS_V0_10: Map<String, Vec<ClubStakingDetails>> =
+    Map::new("club_staking_details");
+
+/// Pre 0.10.0 view of CLUB_STAKING_DETAILS_V0_10, same namespace
+pub const LEGACY_CLUB_STAKING_DETAILS: Map<String, Vec<LegacyClubStakingDetails>> =
     Map::new("club_staking_details");
 
 /// Map of clubs and its bonders. the key is club name and the
@@ -110,13 +392,67 @@ pub const CLUB_STAKING_DETAILS: Map<String, Vec<ClubStakingDetails>> =
 pub const CLUB_BONDING_DETAILS: Map<String, Vec<ClubBondingDetails>> =
     Map::new("club_bonding_details");
 
-/// Map of clubs and its previous owners. the key is club name and the
-/// ClubPreviousOwnerDetails will contain information about the 
-/// previous owner of the club and his reward points
-pub const CLUB_PREVIOUS_OWNER_DETAILS: Map<String, ClubPreviousOwnerDetails> =
+/// The id the next bond of the club gets
+pub const CLUB_BOND_SEQUENCE: Map<String, u64> = Map::new("club_bond_sequence");
+
+/// Reverse index of CLUB_BONDING_DETAILS. the key is (bonder address, club
+/// name) for every club the bonder has bonds waiting in
+pub const BONDER_CLUBS: Map<(String, String), Empty> = Map::new("bonder_clubs");
+
+/// Map of clubs and its previous owners. the key is (club name, previous
+/// owner address) and the ClubPreviousOwnerDetails will contain the reward
+/// points the previous owner has not claimed yet. A balance survives every
+/// later sale of the club.
+pub const CLUB_PREVIOUS_OWNER_DETAILS: Map<(String, String), ClubPreviousOwnerDetails> =
+    Map::new("club_previous_owners");
+
+/// Reverse index of CLUB_OWNERSHIP_DETAILS. the key is (owner address, club name)
+pub const OWNER_CLUBS: Map<(String, String), Empty> = Map::new("owner_clubs");
+
+/// Reverse index of CLUB_PREVIOUS_OWNER_DETAILS. the key is (previous owner
+/// address, club name)
+pub const PREVIOUS_OWNER_CLUBS: Map<(String, String), Empty> = Map::new("previous_owner_clubs");
+
+/// Pre 0.15.0 layout of the previous owners, only the latest one per club
+pub const CLUB_PREVIOUS_OWNER_DETAILS_V0_14: Map<String, ClubPreviousOwnerDetails> =
     Map::new("club_previous_owner_details");
 
+/// One owner of a club, kept after the club changes hands
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClubOwnershipRecord {
+    pub club_name: String,
+    /// Position in the history of the club, the first owner being 0
+    pub sequence: u64,
+    pub owner_address: String,
+    pub acquired_at: Timestamp,
+    /// When the club was sold on, None for the current owner
+    pub released_at: Option<Timestamp>,
+    pub price_paid: Uint128,
+    /// Buying reward plus every owner reward handed out while owning the club
+    pub rewards_earned: Uint128,
+}
+
+/// Append only ownership history. the key is (club name, sequence)
+pub const CLUB_OWNERSHIP_HISTORY: Map<(String, U64Key), ClubOwnershipRecord> =
+    Map::new("club_ownership_history");
+
+/// Map of clubs and the number of records in their ownership history
+pub const CLUB_OWNERSHIP_HISTORY_LENGTH: Map<String, u64> =
+    Map::new("club_ownership_history_length");
+
 pub const CONTRACT_WALLET: Map<&Addr, Uint128> = Map::new("contract_wallet");
 
+/// Tokens the contract holds on behalf of its users: every CONTRACT_WALLET
+/// balance plus the escrowed auction bids and offers. Rewards are only paid
+/// out of the token balance above this.
+pub const ESCROWED_FUNDS: Item<Uint128> = Item::new("escrowed_funds");
+
 pub const REWARD: Item<Uint128> = Item::new("staking_reward");
 
+/// Early withdrawal penalties kept for the stakers, handed out on top of
+/// REWARD by the next reward distribution
+pub const PENALTY_POOL: Item<Uint128> = Item::new("penalty_pool");
+
+/// Map of operators and the roles granted to them by the admin
+pub const ROLES: Map<&Addr, Vec<Role>> = Map::new("roles");
+